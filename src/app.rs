@@ -1,155 +1,424 @@
 use eframe::egui;
-use std::{collections::HashMap, sync::{mpsc::{Sender, Receiver}, Arc}};
+use std::{collections::{HashMap, HashSet}, sync::{mpsc::{Sender, Receiver}, Arc}, time::{Duration, Instant}};
 use tokio::runtime::Runtime;
 
 use crate::steam;
-use crate::config::{GeneralConfig, save_general_config, load_general_config, SpreadsheetConfig, save_spreadsheet_configs, load_spreadsheet_configs};
-use crate::spreadsheet;
-use crate::mods_table::ModsTable;
+use crate::config::{GeneralConfig, save_general_config, load_general_config, SpreadsheetConfig, save_spreadsheet_configs, load_spreadsheet_configs, GameConfig, save_game_configs, load_game_configs};
+use crate::spreadsheet::{self, SheetSource};
+use crate::jobs::{JobKind, JobManager, JobMessage, JobStatus};
+use crate::jobs_panel::JobsPanel;
+use crate::jobs::JobSender;
+use crate::health_dashboard::{HealthDashboard, DashboardAction};
+use crate::notifications_panel::NotificationsPanel;
+use crate::history_section::HistorySection;
+use crate::heuristics::now_unix;
+use crate::session;
+use crate::session_summary::SessionSummary;
+use crate::mods_table::{ModsTable, TableContext};
 use crate::spreadsheet_section::SpreadsheetSection;
+use crate::state::{Action, AppState, Effect, GameEntry, Store, CollectionState, SubscriptionState, SearchState, ApiKeyStatus};
+use crate::game_settings_section::GameSettingsSection;
+use crate::collection_section::CollectionSection;
+use crate::mod_details::{ModDetails, ModDetailsAction};
+use crate::graph_panel::GraphPanel;
+use crate::changelog;
+use crate::changelog_panel::ChangelogPanel;
+use crate::thumbnails::Thumbnails;
+use crate::dlc::DlcCache;
+use crate::watchlist;
+use crate::watchlist_section::WatchlistSection;
+use crate::search_panel::SearchPanel;
+use crate::subscriptions;
+use crate::subscriptions_section::SubscriptionsSection;
 use crate::status_colors::StatusColors;
 use crate::settings_window::{SettingsWindow, SettingsAction};
+use crate::secrets;
 
-#[derive(Clone, Debug)]
-pub struct WorkshopMod {
-    pub id: String,
-    pub name: Option<String>,   
-    pub status: Option<String>, 
-    pub last_updated: Option<String>,
-    pub local_updated: Option<String>,
-}
-
-impl WorkshopMod {
-    pub fn is_outdated(&self) -> Option<bool> {
-        let local = self.local_updated.as_ref()?.parse::<u64>().ok()?;
-        let remote = self.last_updated.as_ref()?.parse::<u64>().ok()?;
-        Some(local < remote)
-    }
-}
-
-pub struct GameEntry {
-    pub appid: String,
-    pub name: String,
-    pub path: String,
-
-    pub spreadsheet: SpreadsheetConfig,
-    pub sheet_data: Option<Vec<Vec<String>>>,
-    pub mods: Vec<WorkshopMod>,
-}
 
 pub struct MyApp {
-    pub games: Option<Vec<GameEntry>>,
+    pub state: AppState,
     pub user_path: String,
-    pub selected: Option<usize>,
-    pub tx: Sender<(String, String, String)>, // (mod_id, title, updated)
-    pub rx: Receiver<(String, String, String)>,
+    pub general: GeneralConfig,
+    pub api_key: String,
+    pub detected_steam_id: Option<String>, // most recent login, refreshed with the game list
+    pub results_tx: Sender<Action>, // results of the requests made outside of jobs
+    pub results_rx: Receiver<Action>,
+    pub thumbnails: Thumbnails,
+    pub search: SearchPanel,
     pub rt: Arc<Runtime>,
-    pub fetching_all: bool,
-    pub progress: Option<(usize, usize)>, // (done, total)
-    pub sort_by_id_asc: bool,
+    pub jobs: JobManager,
+    pub show_jobs: bool,
+    pub show_dashboard: bool,
+    pub show_session_summary: bool,
+    pub show_notifications: bool,
+    pub next_check: Option<Instant>, // next scheduled check, None while the scheduler is off
+    pub scheduled_fetch_pending: bool, // fetch all games once the scheduled rescans are done
     pub show_all_games: bool,
     pub status_colors: StatusColors,
     pub settings: SettingsWindow,
 }
 
-impl GameEntry {
-    pub fn apply_spreadsheet(&mut self) {
-        if let Some(rows) = &self.sheet_data {
-            if let (Some(header_idx), Some(id_col), Some(status_col)) =
-                (self.spreadsheet.header_row_index, self.spreadsheet.id_col, self.spreadsheet.status_col)
-            {
-                if header_idx < rows.len() {
-                    let mut status_map = HashMap::new();
-                    let mut name_map = HashMap::new();
-
-                    for row in rows.iter().skip(header_idx + 1) {
-                        if let Some(id) = row.get(id_col) {
-                            if let Some(status) = row.get(status_col) {
-                                status_map.insert(id.clone(), status.clone());
-                            }
-                            if let Some(name_col) = self.spreadsheet.name_col {
-                                if let Some(name) = row.get(name_col) {
-                                    name_map.insert(id.clone(), name.clone());
-                                }
-                            }
-                        }
-                    }
 
-                    for m in &mut self.mods {
-                        if let Some(status) = status_map.get(&m.id) {
-                            m.status = Some(status.clone());
-                        }
-                        if let Some(name) = name_map.get(&m.id) {
-                            if m.name.is_none() {
-                                m.name = Some(name.clone());
-                            }
-                        }
+impl MyApp {
+    pub fn save_general(&mut self) {
+        self.general.steam_path = self.user_path.clone();
+        save_general_config(&self.general);
+    }
+
+    pub fn save_watchlists(&self) {
+        // Keep the lists of games that are not installed right now
+        let mut watchlists = watchlist::load_watchlists();
+        for game in self.state.games.iter().flatten() {
+            if game.watchlist.is_empty() {
+                watchlists.remove(&game.appid);
+            } else {
+                watchlists.insert(game.appid.clone(), game.watchlist.clone());
+            }
+        }
+        watchlist::save_watchlists(&watchlists);
+    }
+
+    /// Remember the installed items for the next launch; games not installed now keep their entry.
+    pub fn save_session(&self) {
+        let mut sessions = self.state.last_session.clone();
+        let now = now_unix();
+        for game in self.state.games.iter().flatten() {
+            let items = session::session_items(&game.mods, self.state.last_session.get(&game.appid), now);
+            sessions.insert(game.appid.clone(), items);
+        }
+        session::save_last_session(&sessions);
+    }
+
+    pub fn workshop_client(&self) -> steam::WorkshopClient {
+        steam::WorkshopClient::new(&self.api_key, self.general.fetch_concurrency)
+    }
+
+    pub fn start_fetch(&mut self, game_idx: usize) {
+        let Some(game) = self.state.games.as_ref().and_then(|g| g.get(game_idx)) else {
+            return;
+        };
+
+        let ids: Vec<String> = game.mods.iter().map(|m| m.id.clone()).collect();
+        // Watched items are refreshed along with the installed ones
+        let mut detail_ids = ids.clone();
+        detail_ids.extend(game.watchlist.iter().map(|w| w.id.clone()).filter(|id| !ids.contains(id)));
+
+        let task = self.fetch_task(ids, detail_ids, game.appid.parse().into_iter().collect());
+        self.jobs.spawn(&self.rt, JobKind::Fetch, &game.appid, &game.name, |tx| task.run(tx));
+    }
+
+    /// Fetch remote details for the mods and watchlists of every game at once.
+    /// Items installed for several games are only requested once.
+    pub fn start_fetch_all(&mut self) {
+        let mut ids = Vec::new();
+        let mut watched = Vec::new();
+        let mut seen = HashSet::new();
+        for game in self.state.games.iter().flatten() {
+            ids.extend(game.mods.iter().map(|m| m.id.clone()).filter(|id| seen.insert(id.clone())));
+            watched.extend(game.watchlist.iter().map(|w| w.id.clone()));
+        }
+        let mut detail_ids = ids.clone();
+        detail_ids.extend(watched.into_iter().filter(|id| seen.insert(id.clone())));
+
+        let game_appids = self.state.games.iter().flatten().filter_map(|g| g.appid.parse().ok()).collect();
+        let task = self.fetch_task(ids, detail_ids, game_appids);
+        self.jobs.spawn(&self.rt, JobKind::FetchAll, "", "All games", |tx| task.run(tx));
+    }
+
+    fn fetch_task(&self, ids: Vec<String>, detail_ids: Vec<String>, game_appids: Vec<u32>) -> FetchTask {
+        FetchTask {
+            client: self.workshop_client(),
+            ids,
+            detail_ids,
+            steam_path: self.user_path.clone(),
+            game_appids,
+            known_personas: self.state.personas.names.keys().cloned().collect(),
+            known_apps: DlcCache { apps: self.state.dlc.apps.clone(), ..Default::default() },
+        }
+    }
+
+    pub fn load_sheet(&mut self, game_idx: usize, source: SheetSource) {
+        let Some(game) = self.state.games.as_ref().and_then(|g| g.get(game_idx)) else {
+            return;
+        };
+
+        let appid = game.appid.clone();
+        let show_cached = game.sheet_data.is_none();
+        self.jobs.spawn(&self.rt, JobKind::SheetLoad, &game.appid, &game.name, |tx| async move {
+            // CSV loading is blocking I/O, and so is reading a large cached copy
+            let cached_tx = tx.clone();
+            let load = move || {
+                // Show the last good copy until the download is done
+                if show_cached && let Some(cached) = spreadsheet::load_cached_sheet(&appid, &source) {
+                    cached_tx.send(JobMessage::SheetCached(cached.rows, cached.saved_at));
+                }
+                let rows = source.load()?;
+                spreadsheet::save_cached_sheet(&appid, &source, &rows);
+                Ok::<_, String>(rows)
+            };
+            match tokio::task::spawn_blocking(load).await {
+                Ok(Ok(rows)) => {
+                    tx.send(JobMessage::SheetLoaded(rows));
+                    tx.send(JobMessage::Done);
+                }
+                Ok(Err(error)) => tx.send(JobMessage::Failed(error)),
+                Err(_) => tx.send(JobMessage::Failed("Spreadsheet loading stopped".to_string())),
+            }
+        });
+    }
+
+    /// Scheduled check: reread the app manifests, rescan installed mods and reload the
+    /// sheets, then fetch Workshop details for every game once the scans are done.
+    pub fn run_scheduled_check(&mut self) {
+        let manifests = self
+            .state
+            .games
+            .iter()
+            .flatten()
+            .map(|g| steam::read_app_manifest(&g.path, &g.appid))
+            .collect();
+        let effects = self.state.handle_scheduled_check(manifests);
+        self.run_effects(effects);
+        self.scheduled_fetch_pending = true;
+    }
+
+
+    /// Rescan the game's Workshop folder for installed mods.
+    pub fn start_scan(&mut self, game_idx: usize) {
+        let Some(game) = self.state.games.as_ref().and_then(|g| g.get(game_idx)) else {
+            return;
+        };
+        let (path, appid) = (game.path.clone(), game.appid.clone());
+        self.jobs.spawn(&self.rt, JobKind::Scan, &game.appid, &game.name, |tx| async move {
+            match tokio::task::spawn_blocking(move || steam::get_active_mods(&path, &appid)).await {
+                Ok(local_mods) => {
+                    tx.send(JobMessage::Scanned(local_mods));
+                    tx.send(JobMessage::Done);
+                }
+                Err(_) => tx.send(JobMessage::Failed("Scan stopped".to_string())),
+            }
+        });
+    }
+
+    pub fn load_collection(&mut self, game_idx: usize, collection_id: String) {
+        let Some(game) = self.state.games.as_ref().and_then(|g| g.get(game_idx)) else {
+            return;
+        };
+
+        let appid = game.appid.clone();
+        let tx = self.results_tx.clone();
+        let client = self.workshop_client();
+        self.rt.spawn(async move {
+            let Some(ids) = client.fetch_collection_items(&collection_id).await else {
+                let _ = tx.send(Action::CollectionLoaded(appid, None));
+                return;
+            };
+            let titles: HashMap<String, String> = client.fetch_mods_details(&ids, |_| {})
+                .await
+                .into_iter()
+                .map(|d| (d.id, d.title))
+                .collect();
+            let members = ids
+                .into_iter()
+                .map(|id| {
+                    let title = titles.get(&id).cloned().unwrap_or_default();
+                    (id, title)
+                })
+                .collect();
+            let _ = tx.send(Action::CollectionLoaded(appid, Some(members)));
+        });
+    }
+
+    pub fn load_changelog(&mut self, mod_id: String, remote_updated: Option<String>) {
+        let tx = self.results_tx.clone();
+        self.rt.spawn(async move {
+            let notes = changelog::fetch_changelog(&mod_id).await;
+            let _ = tx.send(Action::ChangelogLoaded(mod_id, remote_updated, notes));
+        });
+    }
+
+    /// The configured SteamID64, or the account that last logged in to Steam.
+    pub fn steam_id(&self) -> Option<String> {
+        Some(self.general.steam_id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .or_else(|| self.detected_steam_id.clone())
+    }
+
+    pub fn compare_subscriptions(&mut self, game_idx: usize) {
+        let Some(game) = self.state.games.as_ref().and_then(|g| g.get(game_idx)) else {
+            return;
+        };
+        let appid = game.appid.clone();
+        let tx = self.results_tx.clone();
+        let Some(steam_id) = self.steam_id() else {
+            let _ = tx.send(Action::SubscriptionsCompared(appid, None));
+            return;
+        };
+
+        let installed: Vec<String> = game.mods.iter().map(|m| m.id.clone()).collect();
+        let client = self.workshop_client();
+        self.rt.spawn(async move {
+            let diff = subscriptions::compare_subscriptions(&client, &steam_id, &appid, &installed).await;
+            let _ = tx.send(Action::SubscriptionsCompared(appid, diff));
+        });
+    }
+
+    pub fn run_search(&mut self, request_id: u64, query: steam::WorkshopQuery) {
+        let tx = self.results_tx.clone();
+        let client = self.workshop_client();
+        self.rt.spawn(async move {
+            let _ = tx.send(Action::SearchLoaded(request_id, client.query_files(&query).await));
+        });
+    }
+
+    /// Check the key from the settings window; testing also saves the key being tested.
+    pub fn test_api_key(&mut self) {
+        let _ = secrets::save_api_key(&self.api_key);
+        let tx = self.results_tx.clone();
+        let client = self.workshop_client();
+        self.rt.spawn(async move {
+            let _ = tx.send(Action::ApiKeyChecked(client.check_api_key().await));
+        });
+    }
+
+    pub fn dispatch(&mut self, action: Action) {
+        let effects = self.state.update(action);
+        self.run_effects(effects);
+    }
+
+    /// Start the requested jobs, then save each store once.
+    pub fn run_effects(&mut self, effects: Vec<Effect>) {
+        let mut saves = Vec::new();
+        for effect in effects {
+            match effect {
+                Effect::LoadSheet(i, source) => self.load_sheet(i, source),
+                Effect::Scan(i) => self.start_scan(i),
+                Effect::Fetch(i) => self.start_fetch(i),
+                Effect::FetchAll => self.start_fetch_all(),
+                Effect::LoadCollection(i, id) => self.load_collection(i, id),
+                Effect::CompareSubscriptions(i) => self.compare_subscriptions(i),
+                Effect::LoadChangelog(id, remote_updated) => self.load_changelog(id, remote_updated),
+                Effect::Search(request_id, query) => self.run_search(request_id, query),
+                Effect::TestApiKey => self.test_api_key(),
+                Effect::Save(store) => {
+                    if !saves.contains(&store) {
+                        saves.push(store);
                     }
                 }
             }
         }
+        for store in saves {
+            match store {
+                Store::Personas => self.state.personas.save(),
+                Store::Dlc => self.state.dlc.save(),
+                Store::Notifications => self.state.notifications.save(),
+                Store::History => self.state.history.save(),
+                Store::Watchlists => self.save_watchlists(),
+                Store::BrokenStatuses => self.state.broken_statuses.save(),
+                Store::Changelogs => self.state.changelogs.save(),
+            }
+        }
     }
-}
 
-impl MyApp {
     pub fn reload_games(&mut self) {
         let configs = load_spreadsheet_configs();
-
-        self.games = steam::get_installed_games(&self.user_path)
-            .map(|list| {
-                list.into_iter()
-                    .map(|(appid, name, path)| {
-                        let local_mods = steam::get_active_mods(&path, &appid);
-
-                        let mods = local_mods.into_iter()
-                            .map(|(id, local_time)| WorkshopMod {
-                                id,
-                                name: None,
-                                status: None,
-                                last_updated: None,
-                                local_updated: Some(local_time.to_string()),
-                            })
-                            .collect();
-
-                        GameEntry {
-                            appid: appid.clone(),
-                            name,
-                            path,
-                            spreadsheet: configs.get(&appid).cloned().unwrap_or_default(),
-                            sheet_data: None,
-                            mods,
-                        }
-                    })
-                    .collect()
-            });
-        self.selected = None;
+        let game_configs = load_game_configs();
+        let mut watchlists = watchlist::load_watchlists();
+        self.detected_steam_id = steam::most_recent_steam_id(&self.user_path);
+
+        let games = steam::get_installed_games(&self.user_path).map(|list| {
+            list.into_iter()
+                .map(|(appid, name, path)| {
+                    let local_mods = steam::get_active_mods(&path, &appid);
+                    let mut game = GameEntry::new(appid.clone(), name, path, local_mods);
+                    game.manifest = steam::read_app_manifest(&game.path, &appid);
+                    game.settings = game_configs.get(&appid).cloned().unwrap_or_default();
+                    game.spreadsheet = configs.get(&appid).cloned().unwrap_or_default();
+                    game.watchlist = watchlists.remove(&appid).unwrap_or_default();
+                    game
+                })
+                .collect()
+        });
+        let effects = self.state.set_games(games, now_unix());
+        self.run_effects(effects);
+        self.show_session_summary = self.state.has_session_changes();
     }
     
-    pub fn reload_mods_for_selected(&mut self) {
-        if let (Some(i), Some(games)) = (self.selected, self.games.as_mut()) {
-            let game = &mut games[i];
-            let local_mods = steam::get_active_mods(&game.path, &game.appid);
-            let mut existing: HashMap<String, WorkshopMod> =
-                game.mods.drain(..).map(|m| (m.id.clone(), m)).collect();
-
-            game.mods = local_mods
-                .into_iter()
-                .map(|(id, local_time)| {
-                    if let Some(mut m) = existing.remove(&id) {
-                        m.local_updated = Some(local_time.to_string());
-                        m
-                    } else {
-                        WorkshopMod {
-                            id,
-                            name: None,
-                            status: None,
-                            last_updated: None,
-                            local_updated: Some(local_time.to_string()),
-                        }
-                    }
-                })
-                .collect();
+}
+
+/// Everything a fetch job needs, captured on the UI thread.
+struct FetchTask {
+    client: steam::WorkshopClient,
+    ids: Vec<String>,        // installed mods
+    detail_ids: Vec<String>, // installed and watched items
+    steam_path: String,
+    game_appids: Vec<u32>, // games whose DLC are looked up in the app info cache
+    known_personas: HashSet<String>,
+    known_apps: DlcCache,
+}
+
+impl FetchTask {
+    /// Details (with progress) for `detail_ids`, then votes, authors, DLC and
+    /// requirements for the installed `ids`.
+    async fn run(self, tx: JobSender) {
+        let FetchTask { client, ids, detail_ids, steam_path, game_appids, known_personas, known_apps } = self;
+        let total = detail_ids.len();
+        tx.send(JobMessage::Progress(0, total));
+        let mut done = 0;
+        let mut creators = HashSet::new();
+        let details = client.fetch_mods_details(&detail_ids, |batch| {
+            for details in batch {
+                creators.extend(details.creator.clone());
+                tx.send(JobMessage::Details(details.clone()));
+            }
+            done += batch.len();
+            tx.send(JobMessage::Progress(done, total));
+        })
+        .await;
+        if details.is_empty() && total > 0 {
+            tx.send(JobMessage::Failed("Could not reach the Steam Workshop".to_string()));
+            return;
         }
+
+        // The app info cache can be large, read it off the async workers
+        if let Ok(local_dlc) =
+            tokio::task::spawn_blocking(move || steam::read_local_dlc(&steam_path, &game_appids)).await
+        {
+            tx.send(JobMessage::LocalDlc(local_dlc));
+        }
+
+        if client.has_api_key() {
+            let votes = client.fetch_vote_data(&ids).await;
+            tx.send(JobMessage::Votes(votes));
+
+            let unknown: Vec<String> = creators.difference(&known_personas).cloned().collect();
+            if !unknown.is_empty() {
+                let names = client.fetch_persona_names(&unknown).await;
+                tx.send(JobMessage::Personas(names));
+            }
+
+            let dependencies = client.fetch_app_dependencies(&ids).await;
+            let apps = client.fetch_app_info(&known_apps.unknown_apps(dependencies.values())).await;
+            tx.send(JobMessage::AppDependencies(dependencies, apps));
+        }
+
+        let requirements = client.fetch_mods_requirements(&ids).await;
+        let installed: HashSet<&String> = ids.iter().collect();
+        let missing: Vec<String> = requirements
+            .values()
+            .flatten()
+            .filter(|id| !installed.contains(id))
+            .cloned()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let titles = client.fetch_mods_details(&missing, |_| {})
+            .await
+            .into_iter()
+            .map(|d| (d.id, d.title))
+            .collect();
+        tx.send(JobMessage::Requirements(requirements, titles));
+        tx.send(JobMessage::Done);
     }
 }
 
@@ -162,22 +431,43 @@ impl Default for MyApp {
                 .to_string_lossy()
                 .to_string()
         } else {
-            general.steam_path
+            general.steam_path.clone()
         };
 
-        let (tx, rx) = std::sync::mpsc::channel();
+        // Move a key saved by older versions out of general.json; a key already in the
+        // secret store is newer, so the legacy one is only dropped then
+        let mut api_key = secrets::load_api_key();
+        let legacy_key = general.legacy_api_key.clone();
+        if !legacy_key.is_empty() {
+            if !api_key.is_empty() {
+                save_general_config(&general);
+            } else if secrets::save_api_key(&legacy_key).is_ok() {
+                save_general_config(&general);
+                api_key = legacy_key;
+            }
+        }
+
+        let (results_tx, results_rx) = std::sync::mpsc::channel();
         let rt = Arc::new(Runtime::new().expect("Tokio runtime"));
 
         let mut app = Self {
-            games: None,
+            state: AppState::load(),
             user_path: default_path.to_string(),
-            selected: None,
-            tx,
-            rx,
+            general,
+            api_key,
+            detected_steam_id: None,
+            results_tx,
+            results_rx,
+            thumbnails: Thumbnails::new(),
+            search: SearchPanel::new(),
             rt,
-            fetching_all: false,
-            progress: None,
-            sort_by_id_asc: true,
+            jobs: JobManager::new(),
+            show_jobs: false,
+            show_dashboard: false,
+            show_session_summary: false,
+            show_notifications: false,
+            next_check: None,
+            scheduled_fetch_pending: false,
             show_all_games: false,
             status_colors: StatusColors::load_or_create(),
             settings: SettingsWindow::new(),
@@ -191,7 +481,7 @@ impl Default for MyApp {
 impl eframe::App for MyApp {
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        if let Some(games) = &self.games {
+        if let Some(games) = &self.state.games {
             let configs: HashMap<String, SpreadsheetConfig> = games
                 .iter()
                 .filter_map(|g| {
@@ -206,37 +496,70 @@ impl eframe::App for MyApp {
             if !configs.is_empty() {
                 save_spreadsheet_configs(&configs);
             }
+
+            let game_configs: HashMap<String, GameConfig> = games
+                .iter()
+                .filter(|g| g.settings != GameConfig::default())
+                .map(|g| (g.appid.clone(), g.settings.clone()))
+                .collect();
+            save_game_configs(&game_configs);
         }
+        self.save_watchlists();
+        self.save_session();
     }
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
 
-        while let Ok((id, title, updated)) = self.rx.try_recv() {
-            if id == "__done__" {
-                self.fetching_all = false;
-                self.progress = None;
-                continue;
-            }
-            if id == "__progress__" {
-                if let (Ok(done), Ok(total)) = (title.parse::<usize>(), updated.parse::<usize>()) {
-                    self.progress = Some((done, total));
-                }
-                continue;
-            }
-            if let Some(games) = &mut self.games {
-                for g in games {
-                    for m in &mut g.mods {
-                        if m.id == id {
-                            m.name = Some(title.clone());
-                            m.last_updated = Some(updated.clone());
-                        }
-                    }
-                }
+        let mut effects = Vec::new();
+        for (kind, appid, message) in self.jobs.poll() {
+            effects.extend(self.state.handle_job(kind, &appid, message, now_unix()));
+            ctx.request_repaint();
+        }
+        self.run_effects(effects);
+
+        if self.general.check_interval_minutes > 0 {
+            let interval = Duration::from_secs(self.general.check_interval_minutes as u64 * 60);
+            // A shorter interval from the settings applies right away
+            let next = self.next_check.map_or(Instant::now() + interval, |next| next.min(Instant::now() + interval));
+            self.next_check = Some(next);
+            // Wait for running jobs so a check never replaces one started by hand
+            if Instant::now() >= next && !self.jobs.any_running() {
+                self.next_check = Some(Instant::now() + interval);
+                self.run_scheduled_check();
             }
+            ctx.request_repaint_after(next.saturating_duration_since(Instant::now()));
+        } else {
+            self.next_check = None;
+        }
+        if self.scheduled_fetch_pending && !self.jobs.jobs.iter().any(|j| j.kind == JobKind::Scan && j.is_running()) {
+            self.scheduled_fetch_pending = false;
+            self.dispatch(Action::FetchAll);
+        }
+
+        while let Ok(action) = self.results_rx.try_recv() {
+            self.dispatch(action);
+            ctx.request_repaint();
+        }
+        let collection_loading = self.state.games.iter().flatten()
+            .any(|g| matches!(g.collection, CollectionState::Loading));
+        let subscriptions_loading = self.state.games.iter().flatten()
+            .any(|g| matches!(g.subscriptions, SubscriptionState::Loading));
+        if self.thumbnails.poll(ctx) {
             ctx.request_repaint();
         }
+        if self.jobs.any_running()
+            || collection_loading
+            || subscriptions_loading
+            || !self.state.changelogs_loading.is_empty()
+            || self.thumbnails.is_loading()
+            || matches!(self.state.search, SearchState::Loading)
+            || matches!(self.state.api_key_status, ApiKeyStatus::Checking)
+        {
+            ctx.request_repaint_after(std::time::Duration::from_millis(200));
+        }
+        let mut actions = Vec::new();
         egui::TopBottomPanel::bottom("footer").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                if let Some(games) = &self.games {
+                if let Some(games) = &self.state.games {
                     ui.label(format!("Total games: {}", games.len()));
                 } else {
                     ui.label("No games loaded");
@@ -246,9 +569,23 @@ impl eframe::App for MyApp {
                 });
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     if ui.button("⚙ Settings").clicked() {
-                        self.settings.load_from(&self.status_colors);
+                        self.settings.load_from(&self.status_colors, &self.state.broken_statuses);
                         self.settings.open = true;
                     }
+                    let unread = self.state.notifications.unread();
+                    let label = if unread > 0 { format!("🔔 Notifications ({})", unread) } else { "🔔 Notifications".to_string() };
+                    if ui.button(label).clicked() {
+                        self.show_notifications = !self.show_notifications;
+                    }
+                    let running = self.jobs.jobs.iter().filter(|j| j.is_running()).count();
+                    let label = if running > 0 { format!("🗂 Jobs ({} running)", running) } else { "🗂 Jobs".to_string() };
+                    if ui.button(label).clicked() {
+                        self.show_jobs = !self.show_jobs;
+                    }
+                    if let Some(next) = self.next_check {
+                        let minutes = next.saturating_duration_since(Instant::now()).as_secs() / 60 + 1;
+                        ui.weak(format!("Next check in {} min", minutes));
+                    }
                 });
             });
         });
@@ -261,17 +598,32 @@ impl eframe::App for MyApp {
 
                 // 🔹 Checkbox toggle
                 ui.checkbox(&mut self.show_all_games, "Show all games");
+                if ui.button("🩺 Health dashboard").clicked() {
+                    self.show_dashboard = !self.show_dashboard;
+                }
+                if ui.button("🕘 Since last session").clicked() {
+                    self.show_session_summary = !self.show_session_summary;
+                }
 
-                if let Some(games) = &self.games {
+                let checking_all = self.jobs.running("", JobKind::FetchAll).is_some();
+                let selected = self.state.selected;
+                if let Some(games) = &self.state.games {
                     egui::ScrollArea::vertical().show(ui, |ui| {
                         for (i, game) in games.iter().enumerate() {
                             if self.show_all_games || !game.mods.is_empty() {
-                                if ui
-                                    .selectable_label(self.selected == Some(i), &game.name)
-                                    .clicked()
-                                {
-                                    self.selected = Some(i);
-                                }
+                                ui.horizontal(|ui| {
+                                    if ui.selectable_label(selected == Some(i), &game.name).clicked() {
+                                        actions.push(Action::SelectGame(i));
+                                    }
+                                    if self.jobs.is_busy(&game.appid) || checking_all {
+                                        ui.spinner();
+                                    }
+                                    let unread = self.state.notifications.unread_for(&game.appid);
+                                    if unread > 0 {
+                                        ui.colored_label(egui::Color32::YELLOW, format!("🔔{}", unread))
+                                            .on_hover_text(format!("{} unread notifications", unread));
+                                    }
+                                });
                             }
                         }
                     });
@@ -280,79 +632,109 @@ impl eframe::App for MyApp {
                 }
             });
 
-        let ids: Option<Vec<String>> = self.selected
-                        .and_then(|i| self.games.as_ref().map(|g| {
-                            g[i].mods.iter().map(|m| m.id.clone()).collect()
-                        }));
+        let steam_id = self.steam_id();
+        let sort_by_id_asc = self.state.sort_by_id_asc;
 
         // Right: details panel
         egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(games) = &mut self.games {
-                if let Some(i) = self.selected {
+            if let Some(games) = &mut self.state.games {
+                if let Some(i) = self.state.selected {
                     let game = &mut games[i];
-                    if game.sheet_data.is_none() {
-                        if !game.spreadsheet.sheet_url.is_empty() {
-                            if let Some(csv_url) = spreadsheet::to_csv_url(&game.spreadsheet.sheet_url) {
-                                game.sheet_data = spreadsheet::load_csv_from_url(&csv_url);
-                            }
-                        } else if let Some(path) = &game.spreadsheet.sheet_file {
-                            game.sheet_data = spreadsheet::load_csv_from_file(path);
-                        }
-                    }
-
-                    game.apply_spreadsheet();
+                    game.refresh_mods_view(
+                        sort_by_id_asc,
+                        &self.state.personas,
+                        &self.state.dlc,
+                        &self.general.popularity,
+                        now_unix(),
+                    );
+                    let game = &*game;
 
                     ui.heading(&game.name);
                     ui.label(format!("AppID: {}", game.appid));
                     ui.label(format!("Install dir: {}", game.path));
                     ui.separator();
 
-                    SpreadsheetSection::ui(game, ui);
+                    actions.extend(SpreadsheetSection::ui(game, ui));
+                    actions.extend(GameSettingsSection::ui(game, ui));
+                    actions.extend(CollectionSection::ui(game, ui));
+                    if SubscriptionsSection::ui(game, steam_id.as_deref(), !self.api_key.is_empty(), ui) {
+                        actions.push(Action::CompareSubscriptions);
+                    }
+                    actions.extend(WatchlistSection::ui(game, ui));
 
                     ui.separator();
 
-                    let mut reload_mods = false;
+                    let mut cancel_job = None;
 
                     ui.horizontal(|ui| {
-                        if ui.button("Reload Mods List").clicked() {
-                            reload_mods = true;
+                        let scanning = self.jobs.running(&game.appid, JobKind::Scan).is_some();
+                        if ui.add_enabled(!scanning, egui::Button::new("Reload Mods List")).clicked() {
+                            actions.push(Action::Scan);
                         }
 
-                        if self.fetching_all {
-                            if let Some((done, total)) = self.progress {
-                                ui.label(format!("Fetching mods data {}/{}", done, total));
-                            } else {
-                                ui.label("Fetching mods data…");
+                        if ui.button("🔍 Search Workshop").clicked() {
+                            self.search.open = true;
+                        }
+
+                        match self.jobs.latest(&game.appid, JobKind::Fetch) {
+                            Some(job) if job.is_running() => {
+                                if let Some((done, total)) = job.progress {
+                                    ui.label(format!("Fetching mods data {}/{}", done, total));
+                                } else {
+                                    ui.label("Fetching mods data…");
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    cancel_job = Some(job.id);
+                                }
                             }
-                        } else if ui.button("Check all mods against Steam Workshop (batched)").clicked() {
-                            if let Some(ids) = ids {
-                                self.fetching_all = true;
-                                self.progress = Some((0, ids.len()));
-                                let tx = self.tx.clone();
-                                let rt = self.rt.clone();
-                                rt.spawn(async move {
-                                    let details = crate::steam::fetch_mods_details(&ids).await;
-                                    let total = details.len();
-                                    for (i, (id, title, updated)) in details.into_iter().enumerate() {
-                                        let _ = tx.send((id, title, updated));
-                                        let _ = tx.send((
-                                            "__progress__".into(),
-                                            (i + 1).to_string(),
-                                            total.to_string(),
-                                        ));
-                                    }
-                                    let _ = tx.send(("__done__".into(), String::new(), String::new()));
-                                });
+                            job => {
+                                if ui.button("Check all mods against Steam Workshop (batched)").clicked() {
+                                    actions.push(Action::Fetch);
+                                }
+                                if let Some(JobStatus::Failed(error)) = job.map(|j| &j.status) {
+                                    ui.colored_label(egui::Color32::RED, format!("Last fetch failed: {}", error));
+                                }
                             }
                         }
                     });
 
                     ui.separator();
 
-                    ModsTable::ui(game, ui, &mut self.sort_by_id_asc, &self.status_colors);
+                    if let Some(mod_id) = game.selected_mod.clone() {
+                        let timeline = self.state.history.timeline(&game.appid, &mod_id);
+                        match ModDetails::ui(game, &mod_id, timeline, ui) {
+                            ModDetailsAction::Select(id) => actions.push(Action::SelectMod(Some(id))),
+                            ModDetailsAction::Close => actions.push(Action::SelectMod(None)),
+                            ModDetailsAction::None => {}
+                        }
+                    }
+                    if let Some(id) = GraphPanel::ui(game, game.selected_mod.as_deref(), ui) {
+                        actions.push(Action::SelectMod(Some(id)));
+                    }
+                    let changelogs_to_load = ChangelogPanel::ui(
+                        game,
+                        &self.state.changelogs,
+                        &self.state.changelogs_loading,
+                        &self.state.changelogs_failed,
+                        ui,
+                    );
+                    actions.extend(changelogs_to_load.into_iter().map(Action::LoadChangelog));
+
+                    actions.extend(HistorySection::ui(game, &self.state.history, ui));
 
-                    if reload_mods {
-                        self.reload_mods_for_selected();
+                    ui.separator();
+
+                    let thumbnails = self.general.show_thumbnails.then_some((&mut self.thumbnails, &*self.rt));
+                    let context = TableContext {
+                        status_colors: &self.status_colors,
+                        personas: &self.state.personas,
+                        popularity: &self.general.popularity,
+                        dlc: &self.state.dlc,
+                    };
+                    actions.extend(ModsTable::ui(game, ui, &context, thumbnails));
+
+                    if let Some(id) = cancel_job {
+                        self.jobs.cancel(id);
                     }
                 } else {
                     ui.label("Select a game to see details");
@@ -360,28 +742,82 @@ impl eframe::App for MyApp {
             } else {
                 ui.label("Could not find Steam automatically.");
                 ui.horizontal(|ui| {
-                    if ui.button("Choose Steam folder…").clicked() {
-                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                            if let Some(path_str) = path.to_str() {
-                                self.user_path = path_str.to_string();
-                                self.reload_games();
-                                save_general_config(&GeneralConfig {
-                                    steam_path: self.user_path.clone(),
-                                });
-                            }
-                        }
+                    if ui.button("Choose Steam folder…").clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_folder()
+                        && let Some(path_str) = path.to_str()
+                    {
+                        self.user_path = path_str.to_string();
+                        self.reload_games();
+                        self.save_general();
                     }
                     ui.label(format!("Current path: {}", self.user_path));
                 });
             }
         });
-        match self.settings.show(ctx, &mut self.user_path, &mut self.status_colors) {
+        for action in actions {
+            self.dispatch(action);
+        }
+
+        let mut search_query = None;
+        if let Some(game) = self.state.selected_game() {
+            let thumbnails = self.general.show_thumbnails.then_some((&mut self.thumbnails, &*self.rt));
+            search_query = self.search.show(
+                ctx,
+                game,
+                &self.state.search,
+                !self.api_key.is_empty(),
+                thumbnails,
+                &self.state.personas,
+            );
+        }
+        if let Some(query) = search_query {
+            self.dispatch(Action::Search(query));
+        }
+
+        JobsPanel::show(ctx, &mut self.jobs, &mut self.show_jobs);
+
+        if let Some(games) = &self.state.games
+            && let Some(i) = SessionSummary::show(ctx, games, &mut self.show_session_summary)
+        {
+            self.dispatch(Action::SelectGame(i));
+        }
+
+        for action in NotificationsPanel::show(ctx, &self.state.notifications, &mut self.show_notifications) {
+            self.dispatch(action);
+        }
+
+        if let Some(games) = &mut self.state.games {
+            if self.show_dashboard {
+                games.iter_mut().for_each(|g| g.refresh_health(&self.state.broken_statuses));
+            }
+            let check_all = self.jobs.latest("", JobKind::FetchAll);
+            match HealthDashboard::show(ctx, games, check_all, &mut self.show_dashboard) {
+                DashboardAction::CheckAll => self.dispatch(Action::FetchAll),
+                DashboardAction::Select(i) => self.dispatch(Action::SelectGame(i)),
+                DashboardAction::None => {}
+            }
+        }
+
+        match self.settings.show(
+            ctx,
+            &mut self.user_path,
+            &mut self.general,
+            &mut self.api_key,
+            &self.state.api_key_status,
+            &mut self.status_colors,
+        ) {
             SettingsAction::SteamPathChanged => {
+                self.jobs.cancel_all();
                 self.reload_games();
-                save_general_config(&GeneralConfig {
-                    steam_path: self.user_path.clone(),
-                });
+                self.save_general();
+            }
+            SettingsAction::GeneralChanged => self.save_general(),
+            SettingsAction::ApiKeyChanged => {
+                let _ = secrets::save_api_key(&self.api_key);
             }
+            SettingsAction::ApiKeyEdited => self.dispatch(Action::ApiKeyEdited),
+            SettingsAction::TestApiKey => self.dispatch(Action::TestApiKey),
+            SettingsAction::BrokenStatusesChanged(terms) => self.dispatch(Action::SetBrokenStatuses(terms)),
             SettingsAction::None => {}
         }
 
@@ -390,18 +826,3 @@ impl eframe::App for MyApp {
         }
     }
 }
-
-pub fn format_timestamp(raw: Option<&str>) -> String {
-    if let Some(raw) = raw {
-        if let Ok(ts) = raw.parse::<i64>() {
-            if let Ok(dt) = time::OffsetDateTime::from_unix_timestamp(ts) {
-                if let Ok(fmt) = time::format_description::parse("[year]-[month]-[day] [hour]:[minute]") {
-                    return dt.format(&fmt).unwrap_or_default();
-                }
-            }
-        }
-        raw.to_string()
-    } else {
-        String::new()
-    }
-}
\ No newline at end of file
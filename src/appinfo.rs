@@ -0,0 +1,195 @@
+//! Reader for Steam's binary app info cache (`appcache/appinfo.vdf`), which knows
+//! each game's DLC and depots without a store request.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+const MAGIC_V27: u32 = 0x0756_4427;
+const MAGIC_V28: u32 = 0x0756_4428; // adds a SHA-1 of the binary data
+const MAGIC_V29: u32 = 0x0756_4429; // keys moved into a string table
+
+/// App info sections of the requested apps, shaped like `parse_vdf` output
+/// (every value is a string or an object). Apps missing from the cache are left out.
+pub fn read_appinfo(steam_path: &str, appids: &HashSet<u32>) -> HashMap<u32, Value> {
+    let path = Path::new(steam_path).join("appcache").join("appinfo.vdf");
+    fs::read(path)
+        .ok()
+        .and_then(|data| parse_appinfo(&data, appids))
+        .unwrap_or_default()
+}
+
+pub fn parse_appinfo(data: &[u8], appids: &HashSet<u32>) -> Option<HashMap<u32, Value>> {
+    let mut r = Reader { data, pos: 0 };
+    let magic = r.u32()?;
+    let entry_header = match magic {
+        MAGIC_V27 => 40,
+        MAGIC_V28 | MAGIC_V29 => 60,
+        _ => return None,
+    };
+    r.u32()?; // universe
+
+    let strings = if magic == MAGIC_V29 {
+        let offset = usize::try_from(r.u64()?).ok()?;
+        let mut table = Reader { data, pos: offset };
+        let count = table.u32()?;
+        Some((0..count).map(|_| table.cstr()).collect::<Option<Vec<_>>>()?)
+    } else {
+        None
+    };
+
+    let mut apps = HashMap::new();
+    loop {
+        let appid = r.u32()?;
+        if appid == 0 {
+            break;
+        }
+        let size = r.u32()? as usize;
+        let end = r.pos.checked_add(size).filter(|end| *end <= data.len())?;
+        if appids.contains(&appid) {
+            r.pos += entry_header;
+            let mut root = r.map(strings.as_deref())?;
+            // Everything sits under a single "appinfo" key
+            let info = root.remove("appinfo").unwrap_or(Value::Object(root));
+            apps.insert(appid, info);
+        }
+        r.pos = end;
+    }
+    Some(apps)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, n: usize) -> Option<&[u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+
+    fn cstr(&mut self) -> Option<String> {
+        let len = self.data.get(self.pos..)?.iter().position(|b| *b == 0)?;
+        let text = String::from_utf8_lossy(self.bytes(len)?).into_owned();
+        self.pos += 1;
+        Some(text)
+    }
+
+    /// Binary key/value pairs up to the end-of-map marker.
+    fn map(&mut self, strings: Option<&[String]>) -> Option<Map<String, Value>> {
+        let mut map = Map::new();
+        loop {
+            let kind = self.u8()?;
+            if kind == 0x08 {
+                return Some(map);
+            }
+            let key = match strings {
+                Some(strings) => strings.get(self.u32()? as usize)?.clone(),
+                None => self.cstr()?,
+            };
+            let value = match kind {
+                0x00 => Value::Object(self.map(strings)?),
+                0x01 => Value::String(self.cstr()?),
+                0x02 => Value::String((self.u32()? as i32).to_string()),
+                0x03 => Value::String(f32::from_le_bytes(self.bytes(4)?.try_into().ok()?).to_string()),
+                0x04 | 0x06 => Value::String(self.u32()?.to_string()), // pointer, color
+                0x07 => Value::String(self.u64()?.to_string()),
+                0x0a => Value::String((self.u64()? as i64).to_string()),
+                _ => return None,
+            };
+            map.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One app entry whose data is `{"appinfo": {"common": {"type": "DLC"}, "id": 7}}`.
+    fn entry(appid: u32, key: &mut dyn FnMut(&str) -> Vec<u8>) -> Vec<u8> {
+        let mut kv = vec![0x00];
+        kv.extend(key("appinfo"));
+        kv.push(0x00);
+        kv.extend(key("common"));
+        kv.push(0x01);
+        kv.extend(key("type"));
+        kv.extend(b"DLC\0");
+        kv.push(0x08);
+        kv.push(0x02);
+        kv.extend(key("id"));
+        kv.extend(7u32.to_le_bytes());
+        kv.extend([0x08, 0x08]);
+
+        let mut out = appid.to_le_bytes().to_vec();
+        out.extend(((60 + kv.len()) as u32).to_le_bytes());
+        out.extend([0u8; 60]);
+        out.extend(kv);
+        out
+    }
+
+    fn check(apps: HashMap<u32, Value>) {
+        assert_eq!(apps.len(), 1);
+        assert_eq!(apps[&20]["common"]["type"], "DLC");
+        assert_eq!(apps[&20]["id"], "7");
+    }
+
+    #[test]
+    fn reads_requested_apps_with_inline_keys() {
+        let mut data = MAGIC_V28.to_le_bytes().to_vec();
+        data.extend(1u32.to_le_bytes());
+        let mut key = |k: &str| format!("{}\0", k).into_bytes();
+        data.extend(entry(10, &mut key));
+        data.extend(entry(20, &mut key));
+        data.extend(0u32.to_le_bytes());
+
+        check(parse_appinfo(&data, &HashSet::from([20])).unwrap());
+    }
+
+    #[test]
+    fn reads_keys_from_the_string_table() {
+        let mut strings: Vec<String> = Vec::new();
+        let mut key = |k: &str| {
+            let index = strings.iter().position(|s| s == k).unwrap_or_else(|| {
+                strings.push(k.to_string());
+                strings.len() - 1
+            });
+            (index as u32).to_le_bytes().to_vec()
+        };
+        let mut entries = entry(20, &mut key);
+        entries.extend(0u32.to_le_bytes());
+
+        let mut data = MAGIC_V29.to_le_bytes().to_vec();
+        data.extend(1u32.to_le_bytes());
+        data.extend(((16 + entries.len()) as u64).to_le_bytes());
+        data.extend(entries);
+        data.extend((strings.len() as u32).to_le_bytes());
+        for s in &strings {
+            data.extend(s.as_bytes());
+            data.push(0);
+        }
+
+        check(parse_appinfo(&data, &HashSet::from([20])).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_formats() {
+        assert!(parse_appinfo(&[0, 1, 2, 3, 4, 5, 6, 7], &HashSet::new()).is_none());
+    }
+}
@@ -0,0 +1,6 @@
+//! The headless commands without the app window, so they build with `--no-default-features`.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(workshop_whisperer::cli::run(&args));
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::config::{read_json, write_json};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChangeNote {
+    pub timestamp: u64,
+    pub headline: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CachedChangelog {
+    /// Remote `time_updated` at the moment the notes were fetched
+    pub remote_updated: Option<String>,
+    pub entries: Vec<ChangeNote>,
+}
+
+/// Change notes per Workshop item, persisted in the data directory.
+#[derive(Default)]
+pub struct ChangelogCache {
+    pub entries: HashMap<String, CachedChangelog>,
+}
+
+impl ChangelogCache {
+    pub fn load() -> Self {
+        Self {
+            entries: read_json("changelog_cache.json").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let _ = write_json("changelog_cache.json", &self.entries);
+    }
+
+    /// Cached notes, unless the item was updated remotely since they were fetched.
+    pub fn get(&self, id: &str, remote_updated: Option<&str>) -> Option<&[ChangeNote]> {
+        let cached = self.entries.get(id)?;
+        if remote_updated.is_some() && cached.remote_updated.as_deref() != remote_updated {
+            return None;
+        }
+        Some(&cached.entries)
+    }
+}
+
+pub fn changelog_url(id: &str) -> String {
+    format!("https://steamcommunity.com/sharedfiles/filedetails/changelog/{}", id)
+}
+
+pub async fn fetch_changelog(id: &str) -> Option<Vec<ChangeNote>> {
+    // An error page has no notes either, and must not be cached as "no change notes"
+    let response = reqwest::get(changelog_url(id)).await.ok()?.error_for_status().ok()?;
+    let html = response.text().await.ok()?;
+    Some(parse_changelog(&html))
+}
+
+/// Parse the change notes page of a Workshop item, newest entry first.
+/// Each entry is a `changeLogCtn` box whose `<p id="...">` carries the unix timestamp.
+pub fn parse_changelog(html: &str) -> Vec<ChangeNote> {
+    let mut notes = Vec::new();
+
+    for block in html.split("changeLogCtn").skip(1) {
+        let headline = block
+            .find("headline\">")
+            .map(|pos| &block[pos + "headline\">".len()..])
+            .and_then(|rest| rest.find("</div>").map(|end| strip_html(&rest[..end])))
+            .unwrap_or_default();
+
+        let Some(p_pos) = block.find("<p id=\"") else {
+            continue;
+        };
+        let rest = &block[p_pos + "<p id=\"".len()..];
+        let Some(timestamp) = rest.split('"').next().and_then(|id| id.parse::<u64>().ok()) else {
+            continue;
+        };
+        let text = rest
+            .find('>')
+            .map(|start| &rest[start + 1..])
+            .map(|body| &body[..body.find("</p>").unwrap_or(body.len())])
+            .map(strip_html)
+            .unwrap_or_default();
+
+        notes.push(ChangeNote { timestamp, headline, text });
+    }
+
+    notes.sort_by_key(|n| std::cmp::Reverse(n.timestamp));
+    notes
+}
+
+/// Turn a snippet of Steam's HTML into plain text: line breaks for `<br>`/`<li>`, tags dropped.
+fn strip_html(input: &str) -> String {
+    let mut out = String::new();
+    let mut tag = String::new();
+    let mut in_tag = false;
+
+    for c in input.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag.trim_start_matches('/').split([' ', '/']).next().unwrap_or("");
+                if matches!(name, "br" | "li" | "p" | "div") && !out.ends_with('\n') && !out.is_empty() {
+                    out.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => out.push(c),
+        }
+    }
+
+    let decoded = out
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+
+    decoded
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_entries_newest_first() {
+        let notes = parse_changelog(include_str!("../tests/fixtures/changelog_multiple.html"));
+        let stamps: Vec<u64> = notes.iter().map(|n| n.timestamp).collect();
+        assert_eq!(stamps, vec![1710258300, 1706871720, 1700337600]);
+        assert_eq!(notes[0].headline, "Update: 12 Mar @ 3:45pm");
+        assert_eq!(notes[2].headline, "Update: 18 Nov, 2023 @ 8:00pm");
+    }
+
+    #[test]
+    fn converts_markup_to_plain_text() {
+        let notes = parse_changelog(include_str!("../tests/fixtures/changelog_multiple.html"));
+        assert_eq!(
+            notes[0].text,
+            "Fixed a crash when loading saves & worlds.\nUpdated for 1.5\nTweaked \"balance\""
+        );
+        assert_eq!(notes[1].text, "Added new items");
+        assert_eq!(notes[2].text, "");
+    }
+
+    #[test]
+    fn page_without_notes_is_empty() {
+        let notes = parse_changelog(include_str!("../tests/fixtures/changelog_empty.html"));
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn cache_is_invalidated_by_remote_update() {
+        let mut cache = ChangelogCache::default();
+        cache.entries.insert(
+            "1".into(),
+            CachedChangelog { remote_updated: Some("100".into()), entries: Vec::new() },
+        );
+        assert!(cache.get("1", Some("100")).is_some());
+        assert!(cache.get("1", None).is_some());
+        assert!(cache.get("1", Some("200")).is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use egui::{Color32, RichText, Ui};
+
+use crate::state::GameEntry;
+use crate::model::format_timestamp;
+use crate::changelog::{ChangelogCache, changelog_url};
+
+pub struct ChangelogPanel;
+
+impl ChangelogPanel {
+    /// One collapsible entry per outdated mod, listing the change notes newer than the local copy.
+    /// Returns the IDs whose notes should be fetched (requested lazily when a mod is expanded).
+    pub fn ui(
+        game: &GameEntry,
+        cache: &ChangelogCache,
+        loading: &HashSet<String>,
+        failed: &HashSet<String>,
+        ui: &mut Ui,
+    ) -> Vec<String> {
+        let mut to_fetch = Vec::new();
+        let outdated: Vec<_> = game.mods.iter().filter(|m| m.is_outdated() == Some(true)).collect();
+        if outdated.is_empty() {
+            return to_fetch;
+        }
+
+        egui::CollapsingHeader::new(format!("Change notes for outdated mods ({})", outdated.len()))
+            .default_open(false)
+            .show(ui, |ui| {
+                for m in outdated {
+                    let local: u64 = m.local_updated.as_deref().and_then(|t| t.parse().ok()).unwrap_or(0);
+                    let cached = cache.get(&m.id, m.last_updated.as_deref());
+
+                    let title = match cached {
+                        Some(notes) => format!(
+                            "{} ({} new)",
+                            game.title_for(&m.id),
+                            notes.iter().filter(|n| n.timestamp > local).count()
+                        ),
+                        None => game.title_for(&m.id),
+                    };
+
+                    egui::CollapsingHeader::new(title)
+                        .id_salt(("changelog", &m.id))
+                        .show(ui, |ui| {
+                            ui.hyperlink_to("Open change notes on Steam", changelog_url(&m.id));
+                            match cached {
+                                Some(notes) => {
+                                    let newer: Vec<_> = notes.iter().filter(|n| n.timestamp > local).collect();
+                                    if newer.is_empty() {
+                                        ui.label("No change notes were posted since your local copy.");
+                                    }
+                                    for note in newer {
+                                        ui.label(
+                                            RichText::new(format!(
+                                                "{} — {}",
+                                                format_timestamp(Some(&note.timestamp.to_string())),
+                                                note.headline
+                                            ))
+                                            .strong(),
+                                        );
+                                        if !note.text.is_empty() {
+                                            ui.label(&note.text);
+                                        }
+                                        ui.add_space(4.0);
+                                    }
+                                }
+                                None if loading.contains(&m.id) => {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label("Loading change notes…");
+                                    });
+                                }
+                                None if failed.contains(&m.id) => {
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(Color32::RED, "Could not load change notes");
+                                        if ui.button("Retry").clicked() {
+                                            to_fetch.push(m.id.clone());
+                                        }
+                                    });
+                                }
+                                None => to_fetch.push(m.id.clone()),
+                            }
+                        });
+                }
+            });
+
+        to_fetch
+    }
+}
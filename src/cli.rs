@@ -0,0 +1,445 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::model::{WorkshopMod, format_timestamp};
+use crate::config::{load_general_config, load_spreadsheet_configs, BrokenStatuses, SpreadsheetConfig};
+use crate::spreadsheet::{self, SheetSource};
+use crate::{secrets, steam};
+
+const USAGE: &str = "\
+Usage: WorkshopWhisperer [COMMAND]
+
+Without a command the app window opens; workshop-whisperer-cli only runs commands.
+
+Commands:
+  list-games                        Installed games with their Workshop mod counts
+  list-mods --appid <ID>            Installed Workshop items of a game
+  check --appid <ID> [--format json|csv|table] [--sheet <file>]
+                                    Compare installed mods with the Workshop
+  apply-sheet --appid <ID> --sheet <file> [--header-row <N>] [--id-col <N>] [--status-col <N>]
+                                    Show the sheet status of every installed mod
+
+Options:
+  --steam-path <PATH>               Steam folder (defaults to the app's setting)
+
+Exit codes: 0 when done, 1 when check or apply-sheet find outdated, removed or broken mods,
+2 for a bad command line and 3 when Steam, the Workshop or the sheet cannot be read.
+Broken sheet statuses are the ones marked in the app's settings (data/broken_statuses.json).";
+
+/// Exit codes
+const OK: i32 = 0;
+const PROBLEMS_FOUND: i32 = 1;
+const USAGE_ERROR: i32 = 2;
+const RUNTIME_ERROR: i32 = 3;
+
+enum CliError {
+    /// The command line is wrong; the usage text is printed
+    Usage(String),
+    /// Steam, the Workshop or a sheet could not be read
+    Runtime(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => USAGE_ERROR,
+            CliError::Runtime(_) => RUNTIME_ERROR,
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(error: String) -> Self {
+        CliError::Runtime(error)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(error: &str) -> Self {
+        CliError::Runtime(error.to_string())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+struct Args {
+    command: String,
+    appid: Option<String>,
+    steam_path: Option<String>,
+    sheet: Option<String>,
+    format: Format,
+    header_row: Option<usize>,
+    id_col: Option<usize>,
+    status_col: Option<usize>,
+}
+
+impl Args {
+    fn parse(raw: &[String]) -> Result<Self, String> {
+        let mut iter = raw.iter();
+        let command = iter.next().cloned().ok_or("missing command")?;
+        let mut args = Args {
+            command,
+            appid: None,
+            steam_path: None,
+            sheet: None,
+            format: Format::Table,
+            header_row: None,
+            id_col: None,
+            status_col: None,
+        };
+
+        while let Some(flag) = iter.next() {
+            let mut value = || iter.next().cloned().ok_or(format!("{} needs a value", flag));
+            let number = |v: String| v.parse::<usize>().map_err(|_| format!("{} needs a number", flag));
+            match flag.as_str() {
+                "--appid" => args.appid = Some(value()?),
+                "--steam-path" => args.steam_path = Some(value()?),
+                "--sheet" => args.sheet = Some(value()?),
+                "--format" => {
+                    args.format = match value()?.as_str() {
+                        "table" => Format::Table,
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        other => return Err(format!("unknown format '{}'", other)),
+                    }
+                }
+                "--header-row" => args.header_row = Some(number(value()?)?),
+                "--id-col" => args.id_col = Some(number(value()?)?),
+                "--status-col" => args.status_col = Some(number(value()?)?),
+                other => return Err(format!("unknown option '{}'", other)),
+            }
+        }
+        Ok(args)
+    }
+
+    fn steam_path(&self) -> String {
+        self.steam_path.clone().unwrap_or_else(|| {
+            let general = load_general_config();
+            if general.steam_path.is_empty() {
+                steam::default_steam_path()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            } else {
+                general.steam_path
+            }
+        })
+    }
+}
+
+/// Run a command and return the process exit code.
+pub fn run(raw: &[String]) -> i32 {
+    if matches!(raw.first().map(String::as_str), Some("help" | "--help" | "-h")) {
+        println!("{}", USAGE);
+        return OK;
+    }
+    let args = match Args::parse(raw) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            return USAGE_ERROR;
+        }
+    };
+
+    let result = match args.command.as_str() {
+        "list-games" => list_games(&args),
+        "list-mods" => list_mods(&args),
+        "check" => check(&args),
+        "apply-sheet" => apply_sheet(&args),
+        other => Err(CliError::Usage(format!("unknown command '{}'", other))),
+    };
+    result.unwrap_or_else(|error| {
+        match &error {
+            CliError::Usage(message) => eprintln!("error: {}\n\n{}", message, USAGE),
+            CliError::Runtime(message) => eprintln!("error: {}", message),
+        }
+        error.exit_code()
+    })
+}
+
+/// (appid, name, library path) of the game given with `--appid`.
+fn find_game(args: &Args) -> Result<(String, String, String), CliError> {
+    let appid = args.appid.as_ref().ok_or(CliError::Usage("--appid is required".to_string()))?;
+    let games = steam::get_installed_games(&args.steam_path()).ok_or("Steam library not found")?;
+    games
+        .into_iter()
+        .find(|(id, _, _)| id == appid)
+        .ok_or_else(|| CliError::Runtime(format!("game {} is not installed", appid)))
+}
+
+fn installed_mods(appid: &str, path: &str) -> Vec<WorkshopMod> {
+    let mut mods: Vec<WorkshopMod> = steam::get_active_mods(path, appid)
+        .into_iter()
+        .map(|(id, local_time)| WorkshopMod {
+            id,
+            local_updated: Some(local_time.to_string()),
+            ..Default::default()
+        })
+        .collect();
+    mods.sort_by(|a, b| a.id.cmp(&b.id));
+    mods
+}
+
+/// Apply the sheet from `--sheet` or the game's saved spreadsheet settings.
+/// Column flags override the saved settings.
+fn apply_sheet_statuses(args: &Args, appid: &str, mods: &mut [WorkshopMod]) -> Result<(), CliError> {
+    let mut cfg: SpreadsheetConfig = load_spreadsheet_configs().remove(appid).unwrap_or_default();
+    cfg.header_row_index = args.header_row.or(cfg.header_row_index);
+    cfg.id_col = args.id_col.or(cfg.id_col);
+    cfg.status_col = args.status_col.or(cfg.status_col);
+
+    let source = match &args.sheet {
+        Some(file) => SheetSource::File(file.clone()),
+        None => match SheetSource::from_config(&cfg) {
+            Some(source) => source,
+            None => return Ok(()),
+        },
+    };
+    // Fall back to the app's cached copy when the sheet cannot be loaded
+    let rows = match source.load() {
+        Ok(rows) => rows,
+        Err(error) => match spreadsheet::load_cached_sheet(appid, &source) {
+            Some(cached) => {
+                eprintln!("warning: {}; using the cached copy", error);
+                cached.rows
+            }
+            None => return Err(error.into()),
+        },
+    };
+    let statuses = spreadsheet::sheet_statuses(&rows, &cfg)
+        .ok_or_else(|| {
+            CliError::Usage(
+                "choose the header row, ID and status columns (--header-row, --id-col, --status-col)".to_string(),
+            )
+        })?;
+    for m in mods {
+        m.apply_sheet(&statuses);
+    }
+    Ok(())
+}
+
+fn list_games(args: &Args) -> Result<i32, CliError> {
+    let games = steam::get_installed_games(&args.steam_path()).ok_or("Steam library not found")?;
+    for (appid, name, path) in games {
+        let mods = steam::get_active_mods(&path, &appid).len();
+        println!("{}\t{}\t{} mods", appid, name, mods);
+    }
+    Ok(OK)
+}
+
+fn list_mods(args: &Args) -> Result<i32, CliError> {
+    let (appid, _, path) = find_game(args)?;
+    for m in installed_mods(&appid, &path) {
+        println!("{}\t{}", m.id, format_timestamp(m.local_updated.as_deref()));
+    }
+    Ok(OK)
+}
+
+#[derive(Serialize)]
+struct CheckRow {
+    id: String,
+    name: String,
+    sheet_status: String,
+    local_updated: String,
+    remote_updated: String,
+    state: &'static str,
+    missing_requirements: Vec<String>,
+}
+
+impl CheckRow {
+    fn is_problem(&self) -> bool {
+        matches!(self.state, "outdated" | "removed" | "broken")
+    }
+}
+
+fn check(args: &Args) -> Result<i32, CliError> {
+    let (appid, _, path) = find_game(args)?;
+    let mut mods = installed_mods(&appid, &path);
+    let ids: Vec<String> = mods.iter().map(|m| m.id.clone()).collect();
+
+    let rt = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let client = steam::WorkshopClient::new(&secrets::load_api_key(), load_general_config().fetch_concurrency);
+    let (details, requirements) = rt.block_on(async {
+        let details = client.fetch_mods_details(&ids, |_| {}).await;
+        let requirements = client.fetch_mods_requirements(&ids).await;
+        (details, requirements)
+    });
+    if details.is_empty() && !ids.is_empty() {
+        return Err("could not reach the Steam Workshop".into());
+    }
+    for m in &mut mods {
+        if let Some(d) = details.iter().find(|d| d.id == m.id) {
+            m.apply_details(d);
+        }
+        m.requires = requirements.get(&m.id).cloned();
+    }
+    apply_sheet_statuses(args, &appid, &mut mods)?;
+
+    let installed: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let broken = BrokenStatuses::load();
+    let rows: Vec<CheckRow> = mods
+        .iter()
+        .map(|m| CheckRow {
+            id: m.id.clone(),
+            name: m.name.clone().unwrap_or_default(),
+            sheet_status: m.status.clone().unwrap_or_default(),
+            local_updated: format_timestamp(m.local_updated.as_deref()),
+            remote_updated: format_timestamp(m.last_updated.as_deref()),
+            state: if m.removed {
+                "removed"
+            } else if m.is_broken_per_sheet(&broken) {
+                "broken"
+            } else {
+                match m.is_outdated() {
+                    Some(true) => "outdated",
+                    Some(false) => "up to date",
+                    None => "unknown",
+                }
+            },
+            missing_requirements: m.missing_requirements(&installed).into_iter().map(str::to_string).collect(),
+        })
+        .collect();
+
+    print_rows(&rows, args.format)?;
+    Ok(if rows.iter().any(CheckRow::is_problem) { PROBLEMS_FOUND } else { OK })
+}
+
+fn print_rows(rows: &[CheckRow], format: Format) -> Result<(), String> {
+    match format {
+        Format::Json => {
+            println!("{}", serde_json::to_string_pretty(rows).map_err(|e| e.to_string())?);
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(std::io::stdout());
+            let _ = writer.write_record([
+                "id", "name", "sheet_status", "local_updated", "remote_updated", "state", "missing_requirements",
+            ]);
+            for r in rows {
+                let _ = writer.write_record([
+                    r.id.as_str(),
+                    &r.name,
+                    &r.sheet_status,
+                    &r.local_updated,
+                    &r.remote_updated,
+                    r.state,
+                    &r.missing_requirements.join(" "),
+                ]);
+            }
+            writer.flush().map_err(|e| e.to_string())?;
+        }
+        Format::Table => {
+            let name_width = rows.iter().map(|r| r.name.chars().count()).max().unwrap_or(0).clamp(4, 50);
+            println!(
+                "{:<12} {:<name_width$} {:<14} {:<16} {:<16} {:<10} Missing",
+                "ID", "Name", "Sheet", "Local", "Remote", "State"
+            );
+            for r in rows {
+                let name: String = r.name.chars().take(name_width).collect();
+                println!(
+                    "{:<12} {:<name_width$} {:<14} {:<16} {:<16} {:<10} {}",
+                    r.id,
+                    name,
+                    r.sheet_status,
+                    r.local_updated,
+                    r.remote_updated,
+                    r.state,
+                    r.missing_requirements.join(" ")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_sheet(args: &Args) -> Result<i32, CliError> {
+    if args.sheet.is_none() {
+        return Err(CliError::Usage("--sheet is required".to_string()));
+    }
+    let (appid, _, path) = find_game(args)?;
+    let mut mods = installed_mods(&appid, &path);
+    apply_sheet_statuses(args, &appid, &mut mods)?;
+
+    for m in &mods {
+        println!(
+            "{}\t{}\t{}",
+            m.id,
+            m.status.as_deref().unwrap_or("?"),
+            m.name.as_deref().unwrap_or("")
+        );
+    }
+    let broken = BrokenStatuses::load();
+    Ok(if mods.iter().any(|m| m.is_broken_per_sheet(&broken)) { PROBLEMS_FOUND } else { OK })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn row(state: &'static str) -> CheckRow {
+        CheckRow {
+            id: "1".to_string(),
+            name: String::new(),
+            sheet_status: String::new(),
+            local_updated: String::new(),
+            remote_updated: String::new(),
+            state,
+            missing_requirements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_options() {
+        let args = Args::parse(&raw(&["check", "--appid", "294100", "--id-col", "2", "--sheet", "mods.csv"])).unwrap();
+        assert_eq!(args.command, "check");
+        assert_eq!(args.appid.as_deref(), Some("294100"));
+        assert_eq!(args.id_col, Some(2));
+        assert_eq!(args.sheet.as_deref(), Some("mods.csv"));
+        assert!(args.format == Format::Table);
+    }
+
+    #[test]
+    fn parses_formats() {
+        let format = |name: &str| Args::parse(&raw(&["check", "--format", name])).map(|a| a.format);
+        assert!(format("json") == Ok(Format::Json));
+        assert!(format("csv") == Ok(Format::Csv));
+        assert!(format("table") == Ok(Format::Table));
+        assert_eq!(format("xml").err().as_deref(), Some("unknown format 'xml'"));
+    }
+
+    #[test]
+    fn rejects_bad_options() {
+        let error = |args: &[&str]| Args::parse(&raw(args)).err();
+        assert_eq!(error(&[]).as_deref(), Some("missing command"));
+        assert_eq!(error(&["check", "--verbose"]).as_deref(), Some("unknown option '--verbose'"));
+        assert_eq!(error(&["check", "--appid"]).as_deref(), Some("--appid needs a value"));
+        assert_eq!(error(&["check", "--id-col", "B"]).as_deref(), Some("--id-col needs a number"));
+    }
+
+    #[test]
+    fn outdated_removed_and_broken_mods_are_problems() {
+        assert!(row("outdated").is_problem());
+        assert!(row("removed").is_problem());
+        assert!(row("broken").is_problem());
+        assert!(!row("up to date").is_problem());
+        assert!(!row("unknown").is_problem());
+    }
+
+    #[test]
+    fn exit_codes_separate_usage_and_runtime_errors() {
+        assert_eq!(run(&raw(&["--help"])), OK);
+        assert_eq!(run(&raw(&["check", "--format", "xml"])), USAGE_ERROR);
+        assert_eq!(run(&raw(&["remove-mods"])), USAGE_ERROR);
+        assert_eq!(run(&raw(&["list-mods"])), USAGE_ERROR);
+        assert_eq!(CliError::Usage(String::new()).exit_code(), USAGE_ERROR);
+        assert_eq!(CliError::from("could not reach the Steam Workshop").exit_code(), RUNTIME_ERROR);
+    }
+}
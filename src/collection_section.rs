@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use egui::{Color32, Ui};
+use crate::{state::{Action, CollectionState, GameEntry}, steam, widgets};
+
+pub struct CollectionSection;
+
+impl CollectionSection {
+    /// Returns the input edits and the collection to load when the user asks for one.
+    pub fn ui(game: &GameEntry, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        egui::CollapsingHeader::new("Steam Workshop Collection")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Collection link or ID:");
+                    let mut input = game.collection_input.clone();
+                    if ui.text_edit_singleline(&mut input).changed() {
+                        actions.push(Action::SetCollectionInput(input.clone()));
+                    }
+                    let loading = matches!(game.collection, CollectionState::Loading);
+                    if ui.add_enabled(!loading, egui::Button::new("Load")).clicked() {
+                        actions.extend(steam::parse_workshop_id(&input).map(Action::LoadCollection));
+                    }
+                });
+
+                match &game.collection {
+                    CollectionState::Empty => {}
+                    CollectionState::Loading => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Loading collection…");
+                        });
+                    }
+                    CollectionState::Failed => {
+                        ui.colored_label(Color32::RED, "Could not load the collection");
+                    }
+                    CollectionState::Loaded(members) => {
+                        let installed: HashSet<&str> = game.mods.iter().map(|m| m.id.as_str()).collect();
+                        let in_collection: HashSet<&str> = members.iter().map(|(id, _)| id.as_str()).collect();
+
+                        let (present, missing): (Vec<_>, Vec<_>) =
+                            members.iter().partition(|(id, _)| installed.contains(id.as_str()));
+                        let extra: Vec<_> = game
+                            .mods
+                            .iter()
+                            .filter(|m| !in_collection.contains(m.id.as_str()))
+                            .collect();
+
+                        egui::CollapsingHeader::new(format!("Missing ({})", missing.len()))
+                            .id_salt("collection_missing")
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for (id, title) in &missing {
+                                    widgets::item_link(ui, id, title, Color32::RED);
+                                }
+                            });
+
+                        egui::CollapsingHeader::new(format!("Installed ({})", present.len()))
+                            .id_salt("collection_installed")
+                            .show(ui, |ui| {
+                                for (id, title) in &present {
+                                    widgets::item_link(ui, id, title, Color32::GREEN);
+                                }
+                            });
+
+                        egui::CollapsingHeader::new(format!("Not in collection ({})", extra.len()))
+                            .id_salt("collection_extra")
+                            .show(ui, |ui| {
+                                for m in &extra {
+                                    let title = m.name.as_deref().unwrap_or("<unknown>");
+                                    widgets::item_link(ui, &m.id, title, Color32::YELLOW);
+                                }
+                            });
+                    }
+                }
+            });
+
+        actions
+    }
+}
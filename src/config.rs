@@ -1,7 +1,7 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, fs, path::PathBuf};
 use serde::{Serialize, Deserialize};
 
-fn ensure_data_dir() -> PathBuf {
+pub fn ensure_data_dir() -> PathBuf {
     let exe_dir = std::env::current_exe()
         .ok()
         .and_then(|p| p.parent().map(|p| p.to_path_buf()))
@@ -23,9 +23,72 @@ pub fn write_json<T: Serialize>(filename: &str, value: &T) -> std::io::Result<()
     fs::write(path, json)
 }
 
-#[derive(Serialize, Deserialize, Default, Clone)]
+/// Like [`write_json`] without the indentation, for files that grow large.
+pub fn write_json_compact<T: Serialize>(filename: &str, value: &T) -> std::io::Result<()> {
+    let path = ensure_data_dir().join(filename);
+    let json = serde_json::to_string(value)?;
+    fs::write(path, json)
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct GeneralConfig {
     pub steam_path: String,
+    #[serde(default = "default_fetch_concurrency")]
+    pub fetch_concurrency: usize,
+    #[serde(default = "default_true")]
+    pub show_thumbnails: bool,
+    /// SteamID64 used for account features; empty means the most recent login
+    #[serde(default)]
+    pub steam_id: String,
+    #[serde(default)]
+    pub popularity: PopularityConfig,
+    /// Re-check all games every this many minutes while the app is open (0 = off)
+    #[serde(default)]
+    pub check_interval_minutes: u32,
+    /// Older versions kept the Web API key here; it is moved to `secrets` on startup.
+    #[serde(default, rename = "steam_api_key", skip_serializing)]
+    pub legacy_api_key: String,
+}
+
+fn default_fetch_concurrency() -> usize {
+    4
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for GeneralConfig {
+    fn default() -> Self {
+        Self {
+            steam_path: String::new(),
+            fetch_concurrency: default_fetch_concurrency(),
+            show_thumbnails: true,
+            steam_id: String::new(),
+            popularity: PopularityConfig::default(),
+            check_interval_minutes: 0,
+            legacy_api_key: String::new(),
+        }
+    }
+}
+
+/// Popularity column and the thresholds below which a mod is flagged.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct PopularityConfig {
+    pub show_column: bool,
+    pub min_score: f32,       // vote score 0.0 - 1.0
+    pub min_subscribers: u64,
+}
+
+impl Default for PopularityConfig {
+    fn default() -> Self {
+        Self {
+            show_column: false,
+            min_score: 0.5,
+            min_subscribers: 100,
+        }
+    }
 }
 
 pub fn save_general_config(cfg: &GeneralConfig) {
@@ -64,3 +127,62 @@ pub fn save_spreadsheet_configs(configs: &HashMap<String, SpreadsheetConfig>) {
 pub fn load_spreadsheet_configs() -> HashMap<String, SpreadsheetConfig> {
     read_json("spreadsheet_configs.json").unwrap_or_default()
 }
+
+/// Per-game settings that are not tied to the spreadsheet.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Flag mods without a remote update for this many days as possibly abandoned
+    pub abandoned_days: u32,
+    /// Flag mods whose last remote update predates the game's last update
+    pub flag_older_than_game: bool,
+    /// Build ID or beta branch name -> version label used in Workshop tags (e.g. "1.5")
+    pub versions: BTreeMap<String, String>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            abandoned_days: 365,
+            flag_older_than_game: true,
+            versions: BTreeMap::new(),
+        }
+    }
+}
+
+pub fn save_game_configs(configs: &HashMap<String, GameConfig>) {
+    let _ = write_json("game_configs.json", configs);
+}
+
+pub fn load_game_configs() -> HashMap<String, GameConfig> {
+    read_json("game_configs.json").unwrap_or_default()
+}
+
+/// Sheet statuses that mean a mod does not work, kept lower case. Edited next to the
+/// status colours; the dashboard, history and the `check` command count them as problems.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct BrokenStatuses {
+    pub terms: BTreeSet<String>,
+}
+
+impl Default for BrokenStatuses {
+    fn default() -> Self {
+        let terms = ["broken", "incompatible", "not working", "no", "outdated", "not updated", "abandoned"];
+        Self { terms: terms.iter().map(|t| t.to_string()).collect() }
+    }
+}
+
+impl BrokenStatuses {
+    pub fn load() -> Self {
+        read_json("broken_statuses.json").unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let _ = write_json("broken_statuses.json", self);
+    }
+
+    pub fn contains(&self, status: &str) -> bool {
+        self.terms.contains(&status.trim().to_lowercase())
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::model::WorkshopMod;
+
+/// Directed "requires" graph between a game's Workshop items, built from remote children data.
+/// Required items that are not installed still appear as nodes.
+#[derive(Default)]
+pub struct DependencyGraph {
+    pub nodes: Vec<String>,
+    requires: HashMap<String, Vec<String>>,
+    required_by: HashMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    pub fn build(mods: &[WorkshopMod]) -> Self {
+        let mut nodes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut requires: HashMap<String, Vec<String>> = HashMap::new();
+        let mut required_by: HashMap<String, Vec<String>> = HashMap::new();
+
+        for m in mods {
+            if seen.insert(m.id.clone()) {
+                nodes.push(m.id.clone());
+            }
+            for dep in m.requires.iter().flatten() {
+                if seen.insert(dep.clone()) {
+                    nodes.push(dep.clone());
+                }
+                requires.entry(m.id.clone()).or_default().push(dep.clone());
+                required_by.entry(dep.clone()).or_default().push(m.id.clone());
+            }
+        }
+
+        Self { nodes, requires, required_by }
+    }
+
+    pub fn requires(&self, id: &str) -> &[String] {
+        self.requires.get(id).map_or(&[], |v| v.as_slice())
+    }
+
+    pub fn required_by(&self, id: &str) -> &[String] {
+        self.required_by.get(id).map_or(&[], |v| v.as_slice())
+    }
+
+    /// True if the item takes part in any "requires" relationship.
+    pub fn is_connected(&self, id: &str) -> bool {
+        !self.requires(id).is_empty() || !self.required_by(id).is_empty()
+    }
+
+    /// Every item that directly or indirectly requires `id`, i.e. what breaks if it is removed.
+    pub fn dependents(&self, id: &str) -> Vec<String> {
+        let mut seen = HashSet::from([id.to_string()]);
+        let mut stack = vec![id.to_string()];
+        let mut result = Vec::new();
+
+        while let Some(current) = stack.pop() {
+            for parent in self.required_by(&current) {
+                if seen.insert(parent.clone()) {
+                    result.push(parent.clone());
+                    stack.push(parent.clone());
+                }
+            }
+        }
+        result
+    }
+
+    /// Groups of items that require each other in a loop (Tarjan's strongly connected components).
+    pub fn cycles(&self) -> Vec<Vec<String>> {
+        let mut state = Tarjan::default();
+        for id in &self.nodes {
+            if !state.index.contains_key(id) {
+                state.visit(self, id);
+            }
+        }
+        state.components
+            .into_iter()
+            .filter(|c| c.len() > 1 || self.requires(&c[0]).contains(&c[0]))
+            .collect()
+    }
+
+    /// Shortest loop of "requires" edges from `id` back to itself, staying inside `cycle`
+    /// (one of the groups from `cycles`). Starts and ends with `id`.
+    pub fn cycle_path(&self, cycle: &[String], id: &str) -> Vec<String> {
+        let mut previous: HashMap<&str, &str> = HashMap::new();
+        let mut queue = VecDeque::from([id]);
+        while let Some(current) = queue.pop_front() {
+            for dep in self.requires(current).iter().filter(|dep| cycle.contains(dep)) {
+                if dep == id {
+                    // Walk back to the start, then reverse
+                    let mut path = vec![id.to_string()];
+                    let mut node = current;
+                    while node != id {
+                        path.push(node.to_string());
+                        node = previous[node];
+                    }
+                    path.push(id.to_string());
+                    path.reverse();
+                    return path;
+                }
+                if !previous.contains_key(dep.as_str()) {
+                    previous.insert(dep, current);
+                    queue.push_back(dep);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Column of each node for drawing: items requiring nothing sit at 0,
+    /// everything else one column right of its deepest requirement.
+    pub fn layers(&self) -> HashMap<String, usize> {
+        fn depth(
+            graph: &DependencyGraph,
+            id: &str,
+            memo: &mut HashMap<String, usize>,
+            visiting: &mut HashSet<String>,
+        ) -> usize {
+            if let Some(d) = memo.get(id) {
+                return *d;
+            }
+            // Break cycles by treating a back edge as a root
+            if !visiting.insert(id.to_string()) {
+                return 0;
+            }
+            let d = graph
+                .requires(id)
+                .iter()
+                .map(|dep| depth(graph, dep, memo, visiting) + 1)
+                .max()
+                .unwrap_or(0);
+            visiting.remove(id);
+            memo.insert(id.to_string(), d);
+            d
+        }
+
+        let mut memo = HashMap::new();
+        let mut visiting = HashSet::new();
+        for id in &self.nodes {
+            depth(self, id, &mut memo, &mut visiting);
+        }
+        memo
+    }
+}
+
+#[derive(Default)]
+struct Tarjan {
+    next: usize,
+    index: HashMap<String, usize>,
+    lowlink: HashMap<String, usize>,
+    stack: Vec<String>,
+    on_stack: HashSet<String>,
+    components: Vec<Vec<String>>,
+}
+
+impl Tarjan {
+    fn visit(&mut self, graph: &DependencyGraph, id: &str) {
+        self.index.insert(id.to_string(), self.next);
+        self.lowlink.insert(id.to_string(), self.next);
+        self.next += 1;
+        self.stack.push(id.to_string());
+        self.on_stack.insert(id.to_string());
+
+        for dep in graph.requires(id) {
+            if !self.index.contains_key(dep) {
+                self.visit(graph, dep);
+                let low = self.lowlink[id].min(self.lowlink[dep]);
+                self.lowlink.insert(id.to_string(), low);
+            } else if self.on_stack.contains(dep) {
+                let low = self.lowlink[id].min(self.index[dep]);
+                self.lowlink.insert(id.to_string(), low);
+            }
+        }
+
+        if self.lowlink[id] == self.index[id] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(&member);
+                let done = member == id;
+                component.push(member);
+                if done {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> DependencyGraph {
+        let mods: Vec<WorkshopMod> = edges
+            .iter()
+            .map(|(id, requires)| WorkshopMod {
+                id: id.to_string(),
+                requires: Some(requires.iter().map(|r| r.to_string()).collect()),
+                ..Default::default()
+            })
+            .collect();
+        DependencyGraph::build(&mods)
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn finds_a_cycle_between_mods() {
+        let g = graph(&[("1", &["2"]), ("2", &["3"]), ("3", &["1"]), ("4", &["1"])]);
+        let cycles = g.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(sorted(cycles[0].clone()), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn cycle_path_follows_requires_edges() {
+        // 1 -> 3 -> 2 -> 1, plus a shortcut 3 -> 1
+        let g = graph(&[("1", &["3"]), ("2", &["1"]), ("3", &["2", "1"])]);
+        let cycle = &g.cycles()[0];
+        assert_eq!(g.cycle_path(cycle, "1"), ["1", "3", "1"]);
+        assert_eq!(g.cycle_path(cycle, "2"), ["2", "1", "3", "2"]);
+
+        let g = graph(&[("1", &["1"])]);
+        assert_eq!(g.cycle_path(&g.cycles()[0], "1"), ["1", "1"]);
+    }
+
+    #[test]
+    fn a_mod_requiring_itself_is_a_cycle() {
+        let g = graph(&[("1", &["1"]), ("2", &["1"])]);
+        assert_eq!(g.cycles(), vec![vec!["1".to_string()]]);
+    }
+
+    #[test]
+    fn no_cycles_in_a_chain() {
+        let g = graph(&[("1", &["2"]), ("2", &["3"]), ("3", &[])]);
+        assert!(g.cycles().is_empty());
+        assert_eq!(g.layers()["1"], 2);
+        assert_eq!(g.layers()["3"], 0);
+    }
+
+    #[test]
+    fn dependents_include_indirect_requirers() {
+        // 3 is required by 2, which is required by 1; 4 requires nothing
+        let g = graph(&[("1", &["2"]), ("2", &["3"]), ("4", &[])]);
+        assert_eq!(sorted(g.dependents("3")), ["1", "2"]);
+        assert_eq!(g.dependents("1"), Vec::<String>::new());
+        assert!(!g.is_connected("4"));
+        // Required items that are not installed are nodes too
+        let g = graph(&[("1", &["9"])]);
+        assert_eq!(g.required_by("9"), ["1"]);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_json, write_json};
+use crate::steam::{AppInfo, AppManifest, LocalDlc};
+
+/// App dependencies of Workshop items and store info about those apps, persisted in the
+/// data directory so the DLC check keeps working offline.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DlcCache {
+    pub dependencies: HashMap<String, Vec<u32>>, // mod_id -> app IDs
+    pub apps: HashMap<u32, AppInfo>,
+    pub games: HashMap<u32, LocalDlc>, // game appid -> DLC from the local app info cache
+}
+
+impl DlcCache {
+    pub fn load() -> Self {
+        read_json("dlc_cache.json").unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let _ = write_json("dlc_cache.json", self);
+    }
+
+    /// App IDs referenced by the given mods that have no store info yet.
+    pub fn unknown_apps<'a>(&self, dependencies: impl Iterator<Item = &'a Vec<u32>>) -> Vec<u32> {
+        let unknown: HashSet<u32> = dependencies
+            .flatten()
+            .filter(|appid| !self.apps.contains_key(appid))
+            .copied()
+            .collect();
+        unknown.into_iter().collect()
+    }
+
+    /// DLC of `game_appid` that the mod needs but that is neither installed nor owned.
+    /// Only apps known to be DLC of this game count; other apps (tools, other games) are not checked.
+    pub fn missing_dlc(&self, mod_id: &str, game_appid: &str, manifest: Option<&AppManifest>) -> Vec<u32> {
+        let Some(dependencies) = self.dependencies.get(mod_id) else {
+            return Vec::new();
+        };
+        let game_appid: Option<u32> = game_appid.parse().ok();
+        let local = game_appid.and_then(|appid| self.games.get(&appid));
+        let installed = manifest.map(|m| &m.installed_dlc);
+
+        dependencies
+            .iter()
+            .filter(|appid| Some(**appid) != game_appid)
+            .filter(|appid| {
+                local.is_some_and(|l| l.dlc.contains(appid))
+                    || self.apps.get(appid).is_some_and(|info| info.dlc_for.is_some() && info.dlc_for == game_appid)
+            })
+            // Steam installs the depots of owned DLC, so only DLC with depots of its own can
+            // be told missing; without the app info cache every store-known DLC is checked
+            .filter(|appid| local.is_none_or(|l| l.with_depots.contains(appid)))
+            .filter(|appid| !installed.is_some_and(|dlc| dlc.contains(appid)))
+            .copied()
+            .collect()
+    }
+
+    pub fn name_for(&self, appid: u32) -> String {
+        self.apps
+            .get(&appid)
+            .map_or_else(|| format!("DLC {}", appid), |info| info.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "294100";
+
+    fn cache(dependencies: &[u32]) -> DlcCache {
+        let mut cache = DlcCache::default();
+        cache.dependencies.insert("1".to_string(), dependencies.to_vec());
+        cache
+    }
+
+    fn store_dlc(cache: &mut DlcCache, appid: u32, dlc_for: Option<u32>) {
+        cache.apps.insert(appid, AppInfo { name: format!("App {}", appid), dlc_for });
+    }
+
+    #[test]
+    fn apps_not_known_as_dlc_are_not_flagged() {
+        let mut cache = cache(&[294100, 228980, 1149640]);
+        store_dlc(&mut cache, 228980, None); // a tool, not DLC
+        assert!(cache.missing_dlc("1", GAME, None).is_empty());
+
+        store_dlc(&mut cache, 1149640, Some(294100));
+        assert_eq!(cache.missing_dlc("1", GAME, None), [1149640]);
+    }
+
+    #[test]
+    fn installed_or_depotless_dlc_is_not_missing() {
+        let mut cache = cache(&[1149640, 1392840, 1826140]);
+        cache.games.insert(
+            294100,
+            LocalDlc {
+                dlc: HashSet::from([1149640, 1392840, 1826140]),
+                with_depots: HashSet::from([1149640, 1392840]),
+            },
+        );
+        let manifest = AppManifest { installed_dlc: HashSet::from([1149640]), ..Default::default() };
+
+        // 1826140 has no depot, so owning it leaves no trace to check
+        assert_eq!(cache.missing_dlc("1", GAME, Some(&manifest)), [1392840]);
+    }
+}
@@ -0,0 +1,92 @@
+use egui::Ui;
+
+use crate::state::{Action, GameEntry};
+use crate::model::format_timestamp;
+use crate::heuristics;
+
+pub struct GameSettingsSection;
+
+impl GameSettingsSection {
+    /// Returns the settings edits.
+    pub fn ui(game: &GameEntry, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        egui::CollapsingHeader::new("Game-specific settings")
+            .default_open(false)
+            .show(ui, |ui| {
+                if let Some(manifest) = &game.manifest {
+                    ui.label(format!(
+                        "Build {} · branch {} · game last updated {}",
+                        manifest.buildid.as_deref().unwrap_or("?"),
+                        manifest.beta_key.as_deref().unwrap_or("public"),
+                        format_timestamp(manifest.last_updated.map(|t| t.to_string()).as_deref()),
+                    ));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Flag as possibly abandoned after");
+                    let mut days = game.settings.abandoned_days;
+                    if ui.add(egui::DragValue::new(&mut days).range(0..=3650)).changed() {
+                        actions.push(Action::SetAbandonedDays(days));
+                    }
+                    ui.label("days without an update (0 = off)");
+                });
+                let mut flag = game.settings.flag_older_than_game;
+                if ui.checkbox(&mut flag, "Flag mods not updated since the last game update").changed() {
+                    actions.push(Action::SetFlagOlderThanGame(flag));
+                }
+
+                ui.separator();
+                ui.label("Game versions (build ID or beta branch → version tag used on the Workshop):");
+                for (key, label) in &game.settings.versions {
+                    ui.horizontal(|ui| {
+                        ui.label(key.as_str());
+                        ui.label("→");
+                        let mut label = label.clone();
+                        let edit = egui::TextEdit::singleline(&mut label).desired_width(80.0).hint_text("e.g. 1.5");
+                        if ui.add(edit).changed() {
+                            actions.push(Action::SetVersionLabel(key.clone(), label));
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            actions.push(Action::RemoveVersion(key.clone()));
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    let mut input = game.version_key_input.clone();
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut input)
+                            .desired_width(120.0)
+                            .hint_text("build or branch"),
+                    );
+                    if response.changed() {
+                        actions.push(Action::SetVersionKeyInput(input.clone()));
+                    }
+                    let key = input.trim().to_string();
+                    if ui.add_enabled(!key.is_empty(), egui::Button::new("Add")).clicked() {
+                        actions.push(Action::AddVersion(key));
+                        actions.push(Action::SetVersionKeyInput(String::new()));
+                    }
+                    // Shortcut for the installed build or branch
+                    let current = game
+                        .manifest
+                        .as_ref()
+                        .and_then(|m| m.beta_key.clone().or_else(|| m.buildid.clone()));
+                    if let Some(current) = current
+                        && !game.settings.versions.contains_key(&current)
+                        && ui.button(format!("Add installed ({})", current)).clicked()
+                    {
+                        actions.push(Action::AddVersion(current));
+                    }
+                });
+
+                match heuristics::installed_version(game.manifest.as_ref(), &game.settings) {
+                    Some(version) => ui.label(format!("Installed version: {}", version)),
+                    None => ui.weak("Installed version unknown; compatibility is not checked"),
+                };
+            });
+
+        actions
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, Ui, Vec2};
+
+use crate::state::GameEntry;
+
+const NODE_SIZE: Vec2 = Vec2::new(170.0, 24.0);
+const SPACING: Vec2 = Vec2::new(60.0, 10.0);
+
+pub struct GraphPanel;
+
+impl GraphPanel {
+    /// Draws the items that take part in a dependency, libraries on the left.
+    /// Returns the ID of a clicked node.
+    pub fn ui(game: &GameEntry, selected: Option<&str>, ui: &mut Ui) -> Option<String> {
+        let mut clicked = None;
+        let (graph, cycles) = (&game.mods_view.graph, &game.mods_view.cycles);
+
+        egui::CollapsingHeader::new("Dependency graph")
+            .default_open(false)
+            .show(ui, |ui| {
+                let layers = graph.layers();
+                let mut columns: BTreeMap<usize, Vec<&String>> = BTreeMap::new();
+                for id in graph.nodes.iter().filter(|id| graph.is_connected(id)) {
+                    columns.entry(layers.get(id).copied().unwrap_or(0)).or_default().push(id);
+                }
+                if columns.is_empty() {
+                    ui.label("No dependencies known yet. Check mods against the Workshop first.");
+                    return;
+                }
+
+                let installed: HashSet<&str> = game.mods.iter().map(|m| m.id.as_str()).collect();
+                let in_cycle: HashSet<&str> = cycles.iter().flatten().map(|id| id.as_str()).collect();
+                let rows = columns.values().map(|c| c.len()).max().unwrap_or(0);
+                let size = Vec2::new(
+                    columns.len() as f32 * (NODE_SIZE.x + SPACING.x),
+                    rows as f32 * (NODE_SIZE.y + SPACING.y),
+                );
+
+                egui::ScrollArea::both()
+                    .id_salt("dependency_graph_scroll")
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        let (response, painter) = ui.allocate_painter(size, Sense::hover());
+                        let origin = response.rect.min;
+
+                        let mut rects: HashMap<&str, Rect> = HashMap::new();
+                        for (col, (_, ids)) in columns.iter().enumerate() {
+                            for (row, id) in ids.iter().enumerate() {
+                                let min = origin
+                                    + Vec2::new(
+                                        col as f32 * (NODE_SIZE.x + SPACING.x),
+                                        row as f32 * (NODE_SIZE.y + SPACING.y),
+                                    );
+                                rects.insert(id.as_str(), Rect::from_min_size(min, NODE_SIZE));
+                            }
+                        }
+
+                        let hovered = ui
+                            .ctx()
+                            .pointer_hover_pos()
+                            .and_then(|p| rects.iter().find(|(_, r)| r.contains(p)).map(|(id, _)| *id));
+                        let focus = hovered.or(selected);
+
+                        // Edges first so nodes are drawn on top
+                        for (id, rect) in &rects {
+                            for dep in graph.requires(id) {
+                                if let Some(target) = rects.get(dep.as_str()) {
+                                    let highlighted = focus == Some(*id) || focus == Some(dep.as_str());
+                                    let color = if in_cycle.contains(id) && in_cycle.contains(dep.as_str()) {
+                                        Color32::YELLOW
+                                    } else if highlighted {
+                                        Color32::LIGHT_BLUE
+                                    } else {
+                                        Color32::DARK_GRAY
+                                    };
+                                    Self::arrow(&painter, rect.left_center(), target.right_center(), color);
+                                }
+                            }
+                        }
+
+                        for (id, rect) in &rects {
+                            let response = ui.interact(*rect, ui.id().with(("graph_node", *id)), Sense::click());
+                            if response.clicked() {
+                                clicked = Some(id.to_string());
+                            }
+                            response.on_hover_text(format!(
+                                "{}\nrequires {} / required by {}",
+                                id,
+                                graph.requires(id).len(),
+                                graph.required_by(id).len(),
+                            ));
+
+                            let fill = if !installed.contains(id) {
+                                Color32::from_rgb(110, 30, 30)
+                            } else if selected == Some(*id) {
+                                Color32::from_rgb(40, 70, 110)
+                            } else {
+                                Color32::from_gray(45)
+                            };
+                            let stroke = if focus == Some(*id) {
+                                Stroke::new(2.0, Color32::LIGHT_BLUE)
+                            } else {
+                                Stroke::new(1.0, Color32::GRAY)
+                            };
+                            painter.rect(*rect, 4.0, fill, stroke, egui::StrokeKind::Inside);
+
+                            let mut title = game.title_for(id);
+                            if title.chars().count() > 22 {
+                                title = title.chars().take(21).collect::<String>() + "…";
+                            }
+                            painter.text(
+                                rect.center(),
+                                Align2::CENTER_CENTER,
+                                title,
+                                FontId::proportional(12.0),
+                                Color32::WHITE,
+                            );
+                        }
+                    });
+            });
+
+        clicked
+    }
+
+    fn arrow(painter: &egui::Painter, from: Pos2, to: Pos2, color: Color32) {
+        let stroke = Stroke::new(1.5, color);
+        painter.line_segment([from, to], stroke);
+        let dir = (to - from).normalized();
+        let side = Vec2::new(-dir.y, dir.x) * 4.0;
+        let back = to - dir * 8.0;
+        painter.line_segment([to, back + side], stroke);
+        painter.line_segment([to, back - side], stroke);
+    }
+}
@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+
+use crate::config::BrokenStatuses;
+use crate::state::GameEntry;
+
+/// Problem counts for one game, shown on the health dashboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GameHealth {
+    pub total: usize,
+    pub fetched: usize, // mods with remote details
+    pub outdated: usize,
+    pub removed: usize,
+    pub broken: usize, // per the spreadsheet status
+    pub missing_dependencies: usize,
+}
+
+impl GameHealth {
+    pub fn of(game: &GameEntry, broken: &BrokenStatuses) -> Self {
+        let installed: HashSet<&str> = game.mods.iter().map(|m| m.id.as_str()).collect();
+        let mut health = Self { total: game.mods.len(), ..Default::default() };
+        for m in &game.mods {
+            if m.last_updated.is_some() || m.removed {
+                health.fetched += 1;
+            }
+            if m.is_outdated() == Some(true) {
+                health.outdated += 1;
+            }
+            if m.removed {
+                health.removed += 1;
+            }
+            if m.is_broken_per_sheet(broken) {
+                health.broken += 1;
+            }
+            if !m.missing_requirements(&installed).is_empty() {
+                health.missing_dependencies += 1;
+            }
+        }
+        health
+    }
+
+    pub fn problems(&self) -> usize {
+        self.outdated + self.removed + self.broken + self.missing_dependencies
+    }
+}
@@ -0,0 +1,114 @@
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+
+use crate::state::GameEntry;
+use crate::health::GameHealth;
+use crate::jobs::Job;
+
+pub enum DashboardAction {
+    None,
+    CheckAll,
+    Select(usize), // index into the game list
+}
+
+pub struct HealthDashboard;
+
+impl HealthDashboard {
+    /// Cross-game overview of outdated, removed, broken and missing-dependency mods.
+    /// Shows each game's `health`, which the caller keeps up to date.
+    pub fn show(
+        ctx: &egui::Context,
+        games: &[GameEntry],
+        check_all: Option<&Job>,
+        open: &mut bool,
+    ) -> DashboardAction {
+        let mut action = DashboardAction::None;
+
+        egui::Window::new("Health dashboard")
+            .open(open)
+            .resizable(true)
+            .default_size([620.0, 400.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    match check_all.filter(|j| j.is_running()) {
+                        Some(job) => {
+                            ui.spinner();
+                            match job.progress {
+                                Some((done, total)) => ui.label(format!("Checking all games {}/{}", done, total)),
+                                None => ui.label("Checking all games…"),
+                            };
+                        }
+                        None => {
+                            if ui.button("🔄 Check all games").clicked() {
+                                action = DashboardAction::CheckAll;
+                            }
+                        }
+                    }
+                });
+                ui.separator();
+
+                let mut rows: Vec<(usize, &GameEntry, GameHealth)> = games
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, g)| !g.mods.is_empty())
+                    .map(|(i, g)| (i, g, g.health))
+                    .collect();
+                // Games with the most problems first
+                rows.sort_by_key(|(_, g, h)| (std::cmp::Reverse(h.problems()), g.name.clone()));
+
+                if rows.is_empty() {
+                    ui.label(RichText::new("No games with Workshop mods").weak());
+                    return;
+                }
+
+                let count = |ui: &mut egui::Ui, n: usize, color: Color32| {
+                    if n > 0 {
+                        ui.colored_label(color, n.to_string());
+                    } else {
+                        ui.label(RichText::new("0").weak());
+                    }
+                };
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .column(Column::remainder()) // Game
+                    .column(Column::initial(70.0)) // Mods
+                    .column(Column::initial(70.0)) // Outdated
+                    .column(Column::initial(70.0)) // Removed
+                    .column(Column::initial(70.0)) // Broken
+                    .column(Column::initial(90.0)) // Missing deps
+                    .header(20.0, |mut header| {
+                        header.col(|ui| { ui.heading("Game"); });
+                        header.col(|ui| { ui.heading("Mods"); });
+                        header.col(|ui| { ui.heading("Outdated"); });
+                        header.col(|ui| { ui.heading("Removed"); });
+                        header.col(|ui| { ui.heading("Broken"); });
+                        header.col(|ui| { ui.heading("Missing deps"); });
+                    })
+                    .body(|mut body| {
+                        for (i, game, health) in &rows {
+                            body.row(20.0, |mut row| {
+                                row.col(|ui| {
+                                    if ui.link(&game.name).on_hover_text("Show this game").clicked() {
+                                        action = DashboardAction::Select(*i);
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(format!("{}", health.total)).on_hover_text(format!(
+                                        "{} of {} checked against the Workshop",
+                                        health.fetched, health.total
+                                    ));
+                                });
+                                row.col(|ui| count(ui, health.outdated, Color32::RED));
+                                row.col(|ui| count(ui, health.removed, Color32::RED));
+                                row.col(|ui| count(ui, health.broken, Color32::YELLOW));
+                                row.col(|ui| count(ui, health.missing_dependencies, Color32::RED));
+                            });
+                        }
+                    });
+            });
+
+        action
+    }
+}
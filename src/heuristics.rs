@@ -0,0 +1,125 @@
+use crate::model::WorkshopMod;
+use crate::config::GameConfig;
+use crate::steam::AppManifest;
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Status derived from update timestamps, shown next to the spreadsheet status.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DerivedStatus {
+    PossiblyAbandoned { days: u64 },
+    NotUpdatedSincePatch { days_before: u64 },
+}
+
+impl DerivedStatus {
+    pub fn label(&self) -> String {
+        match self {
+            DerivedStatus::PossiblyAbandoned { days } => format!("Possibly abandoned ({}d)", days),
+            DerivedStatus::NotUpdatedSincePatch { .. } => "Not updated since game patch".to_string(),
+        }
+    }
+
+    /// Label without the day count, which changes daily.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DerivedStatus::PossiblyAbandoned { .. } => "Possibly abandoned",
+            DerivedStatus::NotUpdatedSincePatch { .. } => "Not updated since game patch",
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            DerivedStatus::PossiblyAbandoned { days } => {
+                format!("No Workshop update for {} days", days)
+            }
+            DerivedStatus::NotUpdatedSincePatch { days_before } => {
+                format!("Last Workshop update was {} days before the game's last update", days_before)
+            }
+        }
+    }
+}
+
+/// Derive an abandonment status from remote update age and the game's own update date.
+/// Abandonment takes precedence since it is the stronger signal.
+pub fn derive_status(
+    m: &WorkshopMod,
+    manifest: Option<&AppManifest>,
+    cfg: &GameConfig,
+    now: u64,
+) -> Option<DerivedStatus> {
+    let remote = m.last_updated.as_ref()?.parse::<u64>().ok()?;
+    let days = now.saturating_sub(remote) / DAY;
+    if cfg.abandoned_days > 0 && days >= cfg.abandoned_days as u64 {
+        return Some(DerivedStatus::PossiblyAbandoned { days });
+    }
+
+    if cfg.flag_older_than_game {
+        let game_updated = manifest?.last_updated?;
+        if remote < game_updated {
+            return Some(DerivedStatus::NotUpdatedSincePatch {
+                days_before: (game_updated - remote) / DAY,
+            });
+        }
+    }
+    None
+}
+
+pub fn now_unix() -> u64 {
+    time::OffsetDateTime::now_utc().unix_timestamp().max(0) as u64
+}
+
+/// Version label of the installed build: the beta branch mapping wins over the build ID.
+pub fn installed_version<'a>(manifest: Option<&AppManifest>, cfg: &'a GameConfig) -> Option<&'a str> {
+    let manifest = manifest?;
+    manifest
+        .beta_key
+        .as_ref()
+        .and_then(|branch| cfg.versions.get(branch))
+        .or_else(|| manifest.buildid.as_ref().and_then(|build| cfg.versions.get(build)))
+        .map(|label| label.trim())
+        .filter(|label| !label.is_empty())
+}
+
+/// Tags that look like game versions ("1.5", "v3.12", "1.4.3"), without a leading "v".
+pub fn version_tags(tags: &[String]) -> Vec<&str> {
+    tags.iter()
+        .map(|tag| {
+            let tag = tag.trim();
+            tag.strip_prefix(['v', 'V']).unwrap_or(tag)
+        })
+        .filter(|tag| {
+            tag.contains('.') && tag.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect()
+}
+
+pub enum VersionSupport {
+    Supported,
+    /// The mod declares versions, but not the installed one
+    Unsupported(Vec<String>),
+    /// The mod has no version tags at all
+    Undeclared,
+}
+
+/// Check the mod's version tags against the installed version label.
+/// "1.5" matches an installed "1.5.4" and the other way around.
+pub fn version_support(m: &WorkshopMod, installed: &str) -> Option<VersionSupport> {
+    // Tags are only known once details were fetched; the name may come from the sheet
+    m.last_updated.as_ref()?;
+    let declared = version_tags(&m.tags);
+    if declared.is_empty() {
+        return Some(VersionSupport::Undeclared);
+    }
+
+    let installed = installed.strip_prefix(['v', 'V']).unwrap_or(installed);
+    let matches = |tag: &str| {
+        tag == installed
+            || installed.strip_prefix(tag).is_some_and(|rest| rest.starts_with('.'))
+            || tag.strip_prefix(installed).is_some_and(|rest| rest.starts_with('.'))
+    };
+    if declared.iter().any(|tag| matches(tag)) {
+        Some(VersionSupport::Supported)
+    } else {
+        Some(VersionSupport::Unsupported(declared.iter().map(|t| t.to_string()).collect()))
+    }
+}
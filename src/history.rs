@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::GameEntry;
+use crate::config::{read_json, write_json_compact, BrokenStatuses};
+use crate::heuristics::{self, DerivedStatus};
+use crate::model::{WorkshopMod, format_timestamp};
+
+/// Oldest snapshots of a mod are dropped beyond this
+const MAX_SNAPSHOTS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModState {
+    Unknown, // not fetched yet
+    UpToDate,
+    Outdated,
+    Removed,
+    Broken, // per the spreadsheet
+}
+
+impl ModState {
+    /// `status` is the mod's sheet status, or one carried over from an earlier snapshot.
+    pub fn of(m: &WorkshopMod, status: Option<&str>, broken: &BrokenStatuses) -> Self {
+        if m.removed {
+            ModState::Removed
+        } else if status.is_some_and(|s| broken.contains(s)) {
+            ModState::Broken
+        } else {
+            match m.is_outdated() {
+                Some(true) => ModState::Outdated,
+                Some(false) => ModState::UpToDate,
+                None => ModState::Unknown,
+            }
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModState::Unknown => "Unknown",
+            ModState::UpToDate => "Up to date",
+            ModState::Outdated => "Outdated",
+            ModState::Removed => "Removed",
+            ModState::Broken => "Broken",
+        }
+    }
+}
+
+/// What was known about a mod at one point in time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub time: u64, // unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>, // spreadsheet status
+    pub state: ModState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derived: Option<String>, // heuristic status, without the day count
+}
+
+impl Snapshot {
+    fn same_as(&self, other: &Snapshot) -> bool {
+        self.local == other.local
+            && self.remote == other.remote
+            && self.status == other.status
+            && self.state == other.state
+            && self.derived == other.derived
+    }
+
+    /// Human-readable list of what differs from an earlier snapshot.
+    pub fn differences(&self, before: &Snapshot) -> Vec<String> {
+        let time = |t: Option<u64>| format_timestamp(t.map(|t| t.to_string()).as_deref());
+        let text = |s: &Option<String>| s.clone().unwrap_or_else(|| "-".to_string());
+        let mut changes = Vec::new();
+        if self.remote != before.remote {
+            changes.push(format!("Updated on the Workshop ({})", time(self.remote)));
+        }
+        if self.local != before.local {
+            changes.push(format!("Local copy updated ({})", time(self.local)));
+        }
+        if self.status != before.status {
+            changes.push(format!("Sheet status {} → {}", text(&before.status), text(&self.status)));
+        }
+        if self.state != before.state {
+            changes.push(format!("{} → {}", before.state.label(), self.state.label()));
+        }
+        if self.derived != before.derived {
+            changes.push(format!("{} → {}", text(&before.derived), text(&self.derived)));
+        }
+        changes
+    }
+}
+
+/// A mod whose snapshot changed after the report date.
+pub struct ModChange<'a> {
+    pub mod_id: &'a str,
+    pub before: Option<&'a Snapshot>, // None if first seen after the date
+    pub after: &'a Snapshot,
+}
+
+/// Timestamped snapshots of every mod, persisted in the data directory. A snapshot is
+/// only added when something differs from the previous one, which keeps the store small.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    pub games: HashMap<String, HashMap<String, Vec<Snapshot>>>, // appid -> mod_id -> oldest first
+}
+
+impl History {
+    pub fn load() -> Self {
+        read_json("history.json").unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let _ = write_json_compact("history.json", self);
+    }
+
+    /// Snapshot the game's mods; returns true if anything new was stored.
+    pub fn record(&mut self, game: &GameEntry, broken: &BrokenStatuses, now: u64) -> bool {
+        let mods = self.games.entry(game.appid.clone()).or_default();
+        let mut changed = false;
+        for m in &game.mods {
+            let timeline = mods.entry(m.id.clone()).or_default();
+            let last = timeline.last();
+            let derived = heuristics::derive_status(m, game.manifest.as_ref(), &game.settings, now);
+            // Data not loaded in this session yet counts as unchanged
+            let status = m.status.clone().or_else(|| last.and_then(|s| s.status.clone()));
+            let state = ModState::of(m, status.as_deref(), broken);
+            let snapshot = Snapshot {
+                time: now,
+                local: m.local_updated.as_deref().and_then(|t| t.parse().ok()),
+                remote: m
+                    .last_updated
+                    .as_deref()
+                    .and_then(|t| t.parse().ok())
+                    .or_else(|| last.and_then(|s| s.remote)),
+                status,
+                state: match (state, last) {
+                    // A broken state came from the sheet, which is judged above
+                    (ModState::Unknown, Some(last)) if last.state != ModState::Broken => last.state,
+                    _ => state,
+                },
+                derived: match (&m.last_updated, last) {
+                    (None, Some(last)) => last.derived.clone(),
+                    _ => derived.as_ref().map(DerivedStatus::kind).map(str::to_string),
+                },
+            };
+            if !last.is_some_and(|last| last.same_as(&snapshot)) {
+                timeline.push(snapshot);
+                if timeline.len() > MAX_SNAPSHOTS {
+                    timeline.drain(..timeline.len() - MAX_SNAPSHOTS);
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn timeline(&self, appid: &str, mod_id: &str) -> &[Snapshot] {
+        self.games
+            .get(appid)
+            .and_then(|mods| mods.get(mod_id))
+            .map_or(&[], |t| t.as_slice())
+    }
+
+    /// Mods whose latest snapshot differs from the last one taken at or before `since`,
+    /// most recent change first.
+    pub fn changes_since(&self, appid: &str, since: u64) -> Vec<ModChange<'_>> {
+        let Some(mods) = self.games.get(appid) else {
+            return Vec::new();
+        };
+        let mut changes: Vec<ModChange> = mods
+            .iter()
+            .filter_map(|(mod_id, timeline)| {
+                let after = timeline.last().filter(|s| s.time > since)?;
+                let before = timeline.iter().rev().find(|s| s.time <= since);
+                Some(ModChange { mod_id, before, after })
+            })
+            .filter(|c| c.before.is_none_or(|before| !before.same_as(c.after)))
+            .collect();
+        changes.sort_by_key(|c| std::cmp::Reverse(c.after.time));
+        changes
+    }
+}
+
+/// Unix time of midnight UTC on a "YYYY-MM-DD" date.
+pub fn parse_date(text: &str) -> Option<u64> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month = time::Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    let date = time::Date::from_calendar_date(year, month, day).ok()?;
+    u64::try_from(date.midnight().assume_utc().unix_timestamp()).ok()
+}
+
+/// "YYYY-MM-DD" of a unix time.
+pub fn format_date(unix: u64) -> String {
+    format_timestamp(Some(&unix.to_string())).chars().take(10).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::steam::ItemDetails;
+
+    const APPID: &str = "294100";
+
+    fn game(time: u64) -> GameEntry {
+        let mods = HashMap::from([("1".to_string(), time)]);
+        GameEntry::new(APPID.to_string(), "RimWorld".to_string(), String::new(), mods)
+    }
+
+    fn fetch(game: &mut GameEntry, time_updated: u64) {
+        let details = ItemDetails {
+            id: "1".to_string(),
+            title: "First".to_string(),
+            time_updated: time_updated.to_string(),
+            ..Default::default()
+        };
+        game.mods[0].apply_details(&details);
+    }
+
+    fn states(history: &History) -> Vec<(ModState, Option<&str>)> {
+        history.timeline(APPID, "1").iter().map(|s| (s.state, s.status.as_deref())).collect()
+    }
+
+    #[test]
+    fn unchanged_mods_are_recorded_once() {
+        let broken = BrokenStatuses::default();
+        let mut history = History::default();
+        let mut entry = game(100);
+        fetch(&mut entry, 100);
+        assert!(history.record(&entry, &broken, 1_000));
+        assert!(!history.record(&entry, &broken, 2_000));
+        assert_eq!(history.timeline(APPID, "1").len(), 1);
+    }
+
+    #[test]
+    fn oldest_snapshots_are_dropped() {
+        let broken = BrokenStatuses::default();
+        let mut history = History::default();
+        let mut entry = game(100);
+        for time in 1..=MAX_SNAPSHOTS as u64 + 5 {
+            fetch(&mut entry, time);
+            history.record(&entry, &broken, time * 1_000);
+        }
+        let timeline = history.timeline(APPID, "1");
+        assert_eq!(timeline.len(), MAX_SNAPSHOTS);
+        assert_eq!(timeline[0].time, 6_000);
+    }
+
+    #[test]
+    fn values_not_loaded_this_session_are_carried_forward() {
+        let broken = BrokenStatuses::default();
+        let mut history = History::default();
+        let mut entry = game(100);
+        fetch(&mut entry, 100);
+        entry.mods[0].status = Some("Broken".to_string());
+        history.record(&entry, &broken, 1_000);
+
+        // Next session: fetched before the sheet is loaded, then the sheet arrives
+        let mut entry = game(100);
+        assert!(!history.record(&entry, &broken, 2_000));
+        fetch(&mut entry, 100);
+        assert!(!history.record(&entry, &broken, 3_000));
+        entry.mods[0].status = Some("Broken".to_string());
+        assert!(!history.record(&entry, &broken, 4_000));
+        assert_eq!(states(&history), [(ModState::Broken, Some("Broken"))]);
+
+        // A sheet that no longer lists it as broken is a real change
+        entry.mods[0].status = Some("OK".to_string());
+        assert!(history.record(&entry, &broken, 5_000));
+        assert_eq!(states(&history)[1], (ModState::UpToDate, Some("OK")));
+    }
+
+    #[test]
+    fn changes_since_compares_with_the_last_snapshot_before_the_date() {
+        let broken = BrokenStatuses::default();
+        let mut history = History::default();
+        let mut entry = game(100);
+        fetch(&mut entry, 100);
+        history.record(&entry, &broken, 1_000);
+        fetch(&mut entry, 200);
+        history.record(&entry, &broken, 2_000);
+        entry.mods[0].local_updated = Some("200".to_string());
+        history.record(&entry, &broken, 3_000);
+
+        let changes = history.changes_since(APPID, 1_500);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].before.unwrap().time, 1_000);
+        assert_eq!(changes[0].after.time, 3_000);
+        // Outdated in between and up to date again: only the update times differ
+        assert_eq!(changes[0].after.differences(changes[0].before.unwrap()).len(), 2);
+
+        assert!(history.changes_since(APPID, 3_000).is_empty());
+        assert!(history.changes_since(APPID, 0)[0].before.is_none());
+    }
+
+    #[test]
+    fn parses_dates() {
+        assert_eq!(parse_date("2024-03-01"), Some(1_709_251_200));
+        assert_eq!(parse_date(" 1970-01-02 "), Some(86_400));
+        assert_eq!(format_date(1_709_251_200), "2024-03-01");
+        assert_eq!(parse_date("2024-02-30"), None);
+        assert_eq!(parse_date("yesterday"), None);
+        assert_eq!(parse_date("1969-12-31"), None);
+    }
+}
@@ -0,0 +1,71 @@
+use egui::{Color32, RichText, Ui};
+
+use crate::state::{Action, GameEntry};
+use crate::heuristics::now_unix;
+use crate::history::{self, History};
+use crate::model::format_timestamp;
+
+const DAY: u64 = 24 * 60 * 60;
+
+pub struct HistorySection;
+
+impl HistorySection {
+    /// Report of the mods whose recorded state changed since a date.
+    /// Returns the date edits and the mod to show in the details panel, if one was clicked.
+    pub fn ui(game: &GameEntry, history: &History, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        egui::CollapsingHeader::new("What changed since…")
+            .id_salt("history_report")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Date:");
+                    let mut since = game.history_since.clone();
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut since)
+                            .desired_width(90.0)
+                            .hint_text("YYYY-MM-DD"),
+                    );
+                    if response.changed() {
+                        actions.push(Action::SetHistorySince(since));
+                    }
+                    for (label, days) in [("1 week", 7), ("1 month", 30)] {
+                        if ui.small_button(label).clicked() {
+                            let date = history::format_date(now_unix().saturating_sub(days * DAY));
+                            actions.push(Action::SetHistorySince(date));
+                        }
+                    }
+                });
+
+                let Some(since) = history::parse_date(&game.history_since) else {
+                    if !game.history_since.trim().is_empty() {
+                        ui.colored_label(Color32::RED, "Enter the date as YYYY-MM-DD");
+                    }
+                    return;
+                };
+
+                let changes = history.changes_since(&game.appid, since);
+                if changes.is_empty() {
+                    ui.label(RichText::new("No changes recorded since then").weak());
+                    return;
+                }
+                for change in changes {
+                    ui.horizontal_wrapped(|ui| {
+                        if ui.link(game.title_for(change.mod_id)).clicked() {
+                            actions.push(Action::SelectMod(Some(change.mod_id.to_string())));
+                        }
+                        ui.label(
+                            RichText::new(format_timestamp(Some(&change.after.time.to_string()))).weak(),
+                        );
+                        match change.before {
+                            Some(before) => ui.label(change.after.differences(before).join(" · ")),
+                            None => ui.label(format!("First recorded ({})", change.after.state.label())),
+                        };
+                    });
+                }
+            });
+
+        actions
+    }
+}
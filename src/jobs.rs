@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::mpsc::{Receiver, Sender};
+use std::time::Instant;
+
+use tokio::{runtime::Runtime, task::JoinHandle};
+
+use crate::steam;
+
+pub type JobId = u64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobKind {
+    Fetch,     // Workshop details, votes, requirements
+    FetchAll,  // the same for every installed game, not bound to one
+    SheetLoad, // spreadsheet download or CSV file
+    Scan,      // local Workshop folder scan
+}
+
+impl JobKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            JobKind::Fetch => "Fetch Workshop data",
+            JobKind::FetchAll => "Check all games",
+            JobKind::SheetLoad => "Load spreadsheet",
+            JobKind::Scan => "Scan installed mods",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobStatus {
+    Running,
+    Done,
+    Failed(String),
+    Cancelled,
+}
+
+/// Progress and results reported by a background job.
+pub enum JobMessage {
+    Progress(usize, usize), // (done, total)
+    Details(steam::ItemDetails),
+    /// mod_id -> required item IDs, and titles of the required items that are not installed
+    Requirements(HashMap<String, Vec<String>>, HashMap<String, String>),
+    /// SteamID64 -> persona name for the authors of the fetched mods
+    Personas(HashMap<String, String>),
+    Votes(HashMap<String, steam::VoteData>),
+    /// mod_id -> app IDs the mod depends on, and store info for apps not seen before
+    AppDependencies(HashMap<String, Vec<u32>>, HashMap<u32, steam::AppInfo>),
+    /// game appid -> DLC known from the local app info cache
+    LocalDlc(HashMap<u32, steam::LocalDlc>),
+    /// Rows and save time of the cached copy, sent before the download starts
+    SheetCached(Vec<Vec<String>>, u64),
+    SheetLoaded(Vec<Vec<String>>),
+    /// mod_id -> local update time
+    Scanned(HashMap<String, u64>),
+    Failed(String),
+    Done,
+}
+
+/// Handed to a job's task to report back to the UI thread.
+#[derive(Clone)]
+pub struct JobSender {
+    id: JobId,
+    tx: Sender<(JobId, JobMessage)>,
+}
+
+impl JobSender {
+    pub fn send(&self, message: JobMessage) {
+        let _ = self.tx.send((self.id, message));
+    }
+}
+
+/// A background task bound to the game it was started for.
+pub struct Job {
+    pub id: JobId,
+    pub kind: JobKind,
+    pub appid: String,
+    pub game_name: String,
+    pub progress: Option<(usize, usize)>, // (done, total)
+    pub status: JobStatus,
+    pub started: Instant,
+    handle: JoinHandle<()>,
+}
+
+impl Job {
+    pub fn is_running(&self) -> bool {
+        self.status == JobStatus::Running
+    }
+}
+
+/// Runs jobs on the tokio runtime and keeps track of their progress and outcome.
+/// Several jobs can run at once, but only one of each kind per game.
+pub struct JobManager {
+    pub jobs: Vec<Job>,
+    next_id: JobId,
+    cancelled: Vec<(JobKind, String)>, // cancelled by the user, reported by the next poll
+    tx: Sender<(JobId, JobMessage)>,
+    rx: Receiver<(JobId, JobMessage)>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        Self { jobs: Vec::new(), next_id: 0, cancelled: Vec::new(), tx, rx }
+    }
+
+    /// Start a job, replacing a running job of the same kind for the same game.
+    /// The task must finish with `Done` or `Failed`.
+    pub fn spawn<F, Fut>(&mut self, rt: &Runtime, kind: JobKind, appid: &str, game_name: &str, task: F) -> JobId
+    where
+        F: FnOnce(JobSender) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.cancel_where(|j| j.kind == kind && j.appid == appid);
+        // Only the latest outcome of each kind is kept per game
+        self.jobs.retain(|j| j.is_running() || j.kind != kind || j.appid != appid);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let sender = JobSender { id, tx: self.tx.clone() };
+        let handle = rt.spawn(task(sender));
+        self.jobs.push(Job {
+            id,
+            kind,
+            appid: appid.to_string(),
+            game_name: game_name.to_string(),
+            progress: None,
+            status: JobStatus::Running,
+            started: Instant::now(),
+            handle,
+        });
+        id
+    }
+
+    pub fn cancel(&mut self, id: JobId) {
+        let cancelled = self.cancel_where(|j| j.id == id);
+        self.cancelled.extend(cancelled);
+    }
+
+    /// Stop every job before the games are replaced. Nothing is reported, the games
+    /// the jobs ran for are gone.
+    pub fn cancel_all(&mut self) {
+        self.cancel_where(|_| true);
+        self.cancelled.clear();
+    }
+
+    /// Stop the matching running jobs; returns their kind and game.
+    fn cancel_where(&mut self, pred: impl Fn(&Job) -> bool) -> Vec<(JobKind, String)> {
+        let mut cancelled = Vec::new();
+        for job in self.jobs.iter_mut().filter(|j| j.is_running() && pred(j)) {
+            job.handle.abort();
+            job.status = JobStatus::Cancelled;
+            cancelled.push((job.kind, job.appid.clone()));
+        }
+        cancelled
+    }
+
+    /// The most recent job of `kind` for a game, running or finished.
+    pub fn latest(&self, appid: &str, kind: JobKind) -> Option<&Job> {
+        self.jobs.iter().rev().find(|j| j.appid == appid && j.kind == kind)
+    }
+
+    pub fn running(&self, appid: &str, kind: JobKind) -> Option<&Job> {
+        self.latest(appid, kind).filter(|j| j.is_running())
+    }
+
+    pub fn is_busy(&self, appid: &str) -> bool {
+        self.jobs.iter().any(|j| j.appid == appid && j.is_running())
+    }
+
+    pub fn any_running(&self) -> bool {
+        self.jobs.iter().any(Job::is_running)
+    }
+
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(Job::is_running);
+    }
+
+    /// Drain pending messages and update the job list. Messages of cancelled or replaced
+    /// jobs are dropped; the rest are returned with the job's kind and game. A job cancelled
+    /// by the user is reported as failed, one replaced by a newer job is not.
+    pub fn poll(&mut self) -> Vec<(JobKind, String, JobMessage)> {
+        // Tasks that already ended have sent everything, so check before draining
+        let ended: Vec<JobId> = self
+            .jobs
+            .iter()
+            .filter(|j| j.is_running() && j.handle.is_finished())
+            .map(|j| j.id)
+            .collect();
+
+        let mut messages = Vec::new();
+        while let Ok((id, message)) = self.rx.try_recv() {
+            let Some(job) = self.jobs.iter_mut().find(|j| j.id == id && j.is_running()) else {
+                continue;
+            };
+            match &message {
+                JobMessage::Progress(done, total) => job.progress = Some((*done, *total)),
+                JobMessage::Failed(error) => job.status = JobStatus::Failed(error.clone()),
+                JobMessage::Done => job.status = JobStatus::Done,
+                _ => {}
+            }
+            messages.push((job.kind, job.appid.clone(), message));
+        }
+
+        // A task that panicked never reports back
+        for job in self.jobs.iter_mut().filter(|j| j.is_running() && ended.contains(&j.id)) {
+            let error = "stopped unexpectedly".to_string();
+            job.status = JobStatus::Failed(error.clone());
+            messages.push((job.kind, job.appid.clone(), JobMessage::Failed(error)));
+        }
+        for (kind, appid) in self.cancelled.drain(..) {
+            messages.push((kind, appid, JobMessage::Failed("Cancelled".to_string())));
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_end(jobs: &JobManager) {
+        while !jobs.jobs.iter().all(|j| j.handle.is_finished()) {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn panicked_job_is_marked_failed() {
+        let rt = Runtime::new().unwrap();
+        let mut jobs = JobManager::new();
+        jobs.spawn(&rt, JobKind::Scan, "1", "Game", |_tx| async { panic!("scan failed") });
+        wait_for_end(&jobs);
+
+        let messages = jobs.poll();
+        assert!(matches!(messages.as_slice(), [(JobKind::Scan, _, JobMessage::Failed(_))]));
+        assert!(!jobs.any_running());
+    }
+
+    #[test]
+    fn cancelled_job_reports_failure_but_replaced_job_does_not() {
+        let rt = Runtime::new().unwrap();
+        let mut jobs = JobManager::new();
+        let pending = || std::future::pending::<()>();
+        jobs.spawn(&rt, JobKind::SheetLoad, "1", "Game", |_tx| pending());
+        let id = jobs.spawn(&rt, JobKind::SheetLoad, "1", "Game", |_tx| pending());
+        assert!(jobs.poll().is_empty());
+
+        jobs.cancel(id);
+        let messages = jobs.poll();
+        assert!(matches!(messages.as_slice(), [(JobKind::SheetLoad, _, JobMessage::Failed(_))]));
+        assert_eq!(jobs.latest("1", JobKind::SheetLoad).unwrap().status, JobStatus::Cancelled);
+        assert!(jobs.poll().is_empty());
+    }
+
+    #[test]
+    fn cancelling_all_jobs_for_a_reload_reports_nothing() {
+        let rt = Runtime::new().unwrap();
+        let mut jobs = JobManager::new();
+        let id = jobs.spawn(&rt, JobKind::SheetLoad, "1", "Game", |_tx| std::future::pending::<()>());
+        jobs.spawn(&rt, JobKind::Scan, "1", "Game", |_tx| std::future::pending::<()>());
+
+        jobs.cancel(id);
+        jobs.cancel_all();
+        assert!(jobs.poll().is_empty());
+        assert!(!jobs.any_running());
+    }
+
+    #[test]
+    fn finished_job_keeps_its_outcome() {
+        let rt = Runtime::new().unwrap();
+        let mut jobs = JobManager::new();
+        jobs.spawn(&rt, JobKind::Scan, "1", "Game", |tx| async move { tx.send(JobMessage::Done) });
+        wait_for_end(&jobs);
+
+        assert_eq!(jobs.poll().len(), 1);
+        assert_eq!(jobs.latest("1", JobKind::Scan).unwrap().status, JobStatus::Done);
+    }
+}
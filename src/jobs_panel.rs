@@ -0,0 +1,76 @@
+use egui::{Color32, RichText};
+
+use crate::jobs::{JobManager, JobStatus};
+
+pub struct JobsPanel;
+
+impl JobsPanel {
+    /// Window listing running and finished background jobs.
+    pub fn show(ctx: &egui::Context, jobs: &mut JobManager, open: &mut bool) {
+        let mut cancel = None;
+        let mut clear = false;
+
+        egui::Window::new("Jobs")
+            .open(open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                if jobs.jobs.is_empty() {
+                    ui.label(RichText::new("No jobs").weak());
+                    return;
+                }
+
+                egui::Grid::new("jobs_grid").striped(true).num_columns(4).show(ui, |ui| {
+                    for job in jobs.jobs.iter().rev() {
+                        ui.label(&job.game_name);
+                        ui.label(job.kind.label());
+                        match &job.status {
+                            JobStatus::Running => {
+                                if let Some((done, total)) = job.progress {
+                                    let fraction = if total > 0 { done as f32 / total as f32 } else { 0.0 };
+                                    ui.add(
+                                        egui::ProgressBar::new(fraction)
+                                            .desired_width(140.0)
+                                            .text(format!("{}/{}", done, total)),
+                                    );
+                                } else {
+                                    ui.horizontal(|ui| {
+                                        ui.spinner();
+                                        ui.label(format!("{}s", job.started.elapsed().as_secs()));
+                                    });
+                                }
+                                if ui.small_button("Cancel").clicked() {
+                                    cancel = Some(job.id);
+                                }
+                            }
+                            JobStatus::Done => {
+                                ui.colored_label(Color32::GREEN, "✅ Done");
+                                ui.label("");
+                            }
+                            JobStatus::Failed(error) => {
+                                ui.colored_label(Color32::RED, "❌ Failed").on_hover_text(error);
+                                ui.label(RichText::new(error).weak());
+                            }
+                            JobStatus::Cancelled => {
+                                ui.label(RichText::new("Cancelled").weak());
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                if ui.button("Clear finished").clicked() {
+                    clear = true;
+                }
+            });
+
+        if let Some(id) = cancel {
+            jobs.cancel(id);
+        }
+        if clear {
+            jobs.clear_finished();
+        }
+    }
+}
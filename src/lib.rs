@@ -0,0 +1,13 @@
+//! Core of Steam Workshop Whisperer without any GUI types: the VDF parser, the Steam
+//! library scanner and Workshop client, spreadsheet loading, the saved configuration
+//! and the headless commands. The app in `main.rs` and the command-line build in
+//! `bin/cli.rs` are built on top of this crate.
+
+pub mod parser;
+pub mod steam;
+pub mod appinfo;
+pub mod spreadsheet;
+pub mod config;
+pub mod secrets;
+pub mod model;
+pub mod cli;
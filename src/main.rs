@@ -1,17 +1,51 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] 
 use crate::app::MyApp;
 
-mod steam;
-mod parser;
-mod spreadsheet;
-mod config;
+// The core lives in the library crate
+use workshop_whisperer::{config, model, secrets, spreadsheet, steam};
+
 mod mods_table;
 mod spreadsheet_section;
+mod collection_section;
+mod dependency_graph;
+mod mod_details;
+mod graph_panel;
+mod changelog;
+mod changelog_panel;
+mod thumbnails;
+mod personas;
+mod dlc;
+mod search_panel;
+mod subscriptions;
+mod subscriptions_section;
+mod watchlist;
+mod watchlist_section;
 mod status_colors;
+mod heuristics;
+mod game_settings_section;
 mod settings_window;
+mod jobs;
+mod jobs_panel;
+mod health;
+mod health_dashboard;
+mod notifications;
+mod notifications_panel;
+mod history;
+mod history_section;
+mod session;
+mod session_summary;
+mod state;
+mod widgets;
 mod app;
 
 fn main() -> eframe::Result<()> {
+    // Any arguments select the command-line mode
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        attach_console();
+        std::process::exit(workshop_whisperer::cli::run(&args));
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1000.0, 700.0])
@@ -28,4 +62,19 @@ fn main() -> eframe::Result<()> {
             )
         }),
     )
-}
\ No newline at end of file
+}
+/// Release builds use the Windows GUI subsystem, which starts without a console;
+/// reuse the console of the shell that started us so command output is visible.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+
+use egui::{Color32, RichText, Ui};
+
+use crate::state::GameEntry;
+use crate::model::format_timestamp;
+use crate::steam;
+use crate::history::Snapshot;
+
+pub enum ModDetailsAction {
+    None,
+    Select(String),
+    Close,
+}
+
+pub struct ModDetails;
+
+impl ModDetails {
+    pub fn ui(game: &GameEntry, mod_id: &str, timeline: &[Snapshot], ui: &mut Ui) -> ModDetailsAction {
+        let mut action = ModDetailsAction::None;
+        let (graph, cycles) = (&game.mods_view.graph, &game.mods_view.cycles);
+        let installed: HashSet<&str> = game.mods.iter().map(|m| m.id.as_str()).collect();
+
+        egui::CollapsingHeader::new(format!("Mod details: {}", game.title_for(mod_id)))
+            .id_salt("mod_details")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let url = steam::workshop_url(mod_id);
+                    ui.hyperlink_to(mod_id, url);
+                    if let Some(m) = game.mods.iter().find(|m| m.id == mod_id) {
+                        ui.label(format!(
+                            "local {} / remote {}",
+                            format_timestamp(m.local_updated.as_deref()),
+                            format_timestamp(m.last_updated.as_deref()),
+                        ));
+                    } else {
+                        ui.colored_label(Color32::RED, "not installed");
+                    }
+                    if ui.button("✖").clicked() {
+                        action = ModDetailsAction::Close;
+                    }
+                });
+
+                ui.label("Requires:");
+                Self::id_list(game, graph.requires(mod_id), &installed, ui, &mut action);
+
+                ui.label("Required by:");
+                Self::id_list(game, graph.required_by(mod_id), &installed, ui, &mut action);
+
+                if let Some(cycle) = cycles.iter().find(|c| c.iter().any(|id| id == mod_id)) {
+                    let names: Vec<String> =
+                        graph.cycle_path(cycle, mod_id).iter().map(|id| game.title_for(id)).collect();
+                    ui.colored_label(
+                        Color32::YELLOW,
+                        format!("⚠️ Part of a dependency cycle: {}", names.join(" → ")),
+                    );
+                }
+
+                let dependents: Vec<String> = graph
+                    .dependents(mod_id)
+                    .into_iter()
+                    .filter(|id| installed.contains(id.as_str()))
+                    .collect();
+                if game.mods.iter().any(|m| m.requires.is_none()) {
+                    // Without every mod's requirements the graph may be missing edges
+                    ui.colored_label(Color32::GRAY, "❔ Safe to unsubscribe: unknown – fetch details first");
+                } else if dependents.is_empty() {
+                    ui.colored_label(Color32::GREEN, "✅ Safe to unsubscribe: no installed mod requires it");
+                } else {
+                    ui.colored_label(
+                        Color32::RED,
+                        format!("⚠️ Unsubscribing breaks {} installed mod(s):", dependents.len()),
+                    );
+                    Self::id_list(game, &dependents, &installed, ui, &mut action);
+                }
+
+                Self::timeline(timeline, ui);
+            });
+
+        action
+    }
+
+    /// Recorded snapshots, newest first, with the values that changed in bold.
+    fn timeline(timeline: &[Snapshot], ui: &mut Ui) {
+        egui::CollapsingHeader::new(format!("Timeline ({})", timeline.len()))
+            .id_salt("mod_timeline")
+            .show(ui, |ui| {
+                if timeline.is_empty() {
+                    ui.label(RichText::new("Nothing recorded yet").weak());
+                    return;
+                }
+                let time = |t: Option<u64>| format_timestamp(t.map(|t| t.to_string()).as_deref());
+                egui::Grid::new("mod_timeline_grid").striped(true).num_columns(6).show(ui, |ui| {
+                    for title in ["Recorded", "Local", "Remote", "Sheet", "State", "Derived"] {
+                        ui.label(RichText::new(title).strong());
+                    }
+                    ui.end_row();
+
+                    for (i, s) in timeline.iter().enumerate().rev() {
+                        let prev = i.checked_sub(1).map(|p| &timeline[p]);
+                        let cell = |ui: &mut Ui, text: String, changed: bool| {
+                            if changed { ui.label(RichText::new(text).strong()) } else { ui.label(text) };
+                        };
+                        ui.label(RichText::new(time(Some(s.time))).weak());
+                        cell(ui, time(s.local), prev.is_some_and(|p| p.local != s.local));
+                        cell(ui, time(s.remote), prev.is_some_and(|p| p.remote != s.remote));
+                        cell(ui, s.status.clone().unwrap_or_default(), prev.is_some_and(|p| p.status != s.status));
+                        cell(ui, s.state.label().to_string(), prev.is_some_and(|p| p.state != s.state));
+                        cell(ui, s.derived.clone().unwrap_or_default(), prev.is_some_and(|p| p.derived != s.derived));
+                        ui.end_row();
+                    }
+                });
+            });
+    }
+
+    fn id_list(
+        game: &GameEntry,
+        ids: &[String],
+        installed: &HashSet<&str>,
+        ui: &mut Ui,
+        action: &mut ModDetailsAction,
+    ) {
+        if ids.is_empty() {
+            ui.label("  -");
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for id in ids {
+                let color = if installed.contains(id.as_str()) { Color32::GRAY } else { Color32::RED };
+                if ui
+                    .button(egui::RichText::new(game.title_for(id)).color(color))
+                    .clicked()
+                {
+                    *action = ModDetailsAction::Select(id.clone());
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::config::{BrokenStatuses, PopularityConfig};
+use crate::steam;
+
+#[derive(Clone, Debug, Default)]
+pub struct WorkshopMod {
+    pub id: String,
+    pub name: Option<String>,   
+    pub status: Option<String>, 
+    pub last_updated: Option<String>,
+    pub local_updated: Option<String>,
+    pub requires: Option<Vec<String>>, // required Workshop items, None until fetched
+    pub preview_url: Option<String>,
+    pub creator: Option<String>, // SteamID64 of the author
+    pub subscriptions: Option<u64>,
+    pub favorited: Option<u64>,
+    pub votes: Option<steam::VoteData>,
+    pub tags: Vec<String>,
+    pub removed: bool, // no longer available on the Workshop
+}
+
+impl WorkshopMod {
+    pub fn apply_details(&mut self, details: &steam::ItemDetails) {
+        self.removed = details.removed;
+        if details.removed {
+            return;
+        }
+        self.name = Some(details.title.clone());
+        self.last_updated = Some(details.time_updated.clone());
+        self.preview_url = details.preview_url.clone();
+        self.creator = details.creator.clone();
+        self.subscriptions = details.subscriptions;
+        self.favorited = details.favorited;
+        self.tags = details.tags.clone();
+        if details.votes.is_some() {
+            self.votes = details.votes;
+        }
+    }
+
+    /// Reason to distrust the mod based on votes and subscriber count, if any.
+    pub fn popularity_warning(&self, cfg: &PopularityConfig) -> Option<&'static str> {
+        if self.votes.is_some_and(|v| v.votes_up + v.votes_down > 0 && v.score < cfg.min_score) {
+            Some("Low-rated")
+        } else if self.subscriptions.is_some_and(|s| s < cfg.min_subscribers) {
+            Some("Very few subscribers")
+        } else {
+            None
+        }
+    }
+
+    pub fn is_outdated(&self) -> Option<bool> {
+        let local = self.local_updated.as_ref()?.parse::<u64>().ok()?;
+        let remote = self.last_updated.as_ref()?.parse::<u64>().ok()?;
+        Some(local < remote)
+    }
+
+    /// Take the status from the sheet; the sheet's name is only used until details are fetched.
+    pub fn apply_sheet(&mut self, statuses: &HashMap<String, (String, Option<String>)>) {
+        if let Some((status, name)) = statuses.get(&self.id) {
+            self.status = Some(status.clone());
+            if self.name.is_none() {
+                self.name = name.clone();
+            }
+        }
+    }
+
+    /// Spreadsheet status marks the mod as not working.
+    pub fn is_broken_per_sheet(&self, broken: &BrokenStatuses) -> bool {
+        self.status.as_deref().is_some_and(|s| broken.contains(s))
+    }
+
+    /// Required items that are not part of `installed`.
+    pub fn missing_requirements<'a>(&'a self, installed: &HashSet<&str>) -> Vec<&'a str> {
+        self.requires
+            .iter()
+            .flatten()
+            .map(|id| id.as_str())
+            .filter(|id| !installed.contains(id))
+            .collect()
+    }
+}
+
+pub fn format_timestamp(raw: Option<&str>) -> String {
+    if let Some(raw) = raw {
+        if let Ok(ts) = raw.parse::<i64>()
+            && let Ok(dt) = time::OffsetDateTime::from_unix_timestamp(ts)
+            && let Ok(fmt) = time::format_description::parse("[year]-[month]-[day] [hour]:[minute]")
+        {
+            return dt.format(&fmt).unwrap_or_default();
+        }
+        raw.to_string()
+    } else {
+        String::new()
+    }
+}
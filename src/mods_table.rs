@@ -1,96 +1,325 @@
 use egui::{Color32, Ui};
 use egui_extras::{TableBuilder, Column};
+use tokio::runtime::Runtime;
 
-use crate::app::{GameEntry, format_timestamp};
+use crate::state::{Action, GameEntry};
+use crate::model::format_timestamp;
+use crate::steam;
 use crate::status_colors::StatusColors;
+use crate::thumbnails::Thumbnails;
+use crate::personas::PersonaCache;
+use crate::config::PopularityConfig;
+use crate::dlc::DlcCache;
+use crate::heuristics::VersionSupport;
 
 pub struct ModsTable;
 
+/// Read-only lookups the table draws with.
+pub struct TableContext<'a> {
+    pub status_colors: &'a StatusColors,
+    pub personas: &'a PersonaCache,
+    pub popularity: &'a PopularityConfig,
+    pub dlc: &'a DlcCache,
+}
+
 impl ModsTable {
+    /// Returns the mod selection, sort and author filter changes.
     pub fn ui(
         game: &GameEntry,
         ui: &mut Ui,
-        sort_by_id_asc: &mut bool,
-        status_colors: &StatusColors, // 🔹 pass in reference
-    ) {
+        context: &TableContext,
+        mut thumbnails: Option<(&mut Thumbnails, &Runtime)>, // None when thumbnails are turned off
+    ) -> Vec<Action> {
+        let TableContext { status_colors, personas, popularity, dlc } = *context;
+        let mut actions = Vec::new();
         if game.mods.is_empty() {
             ui.label("No active Workshop mods found.");
-            return;
+            return actions;
         }
 
-        // Make a sorted copy
-        let mut mods_sorted = game.mods.clone();
-        mods_sorted.sort_by(|a, b| a.id.cmp(&b.id));
-        if !*sort_by_id_asc {
-            mods_sorted.reverse();
+        // Everything below comes from the view model, refreshed by the caller
+        let view = &game.mods_view;
+        let counts = &view.counts;
+        if counts.missing_requirements > 0 {
+            ui.colored_label(
+                Color32::RED,
+                format!("⚠️ {} mod(s) require items that are not installed", counts.missing_requirements),
+            );
+        }
+        if counts.missing_dlc > 0 {
+            ui.colored_label(
+                Color32::RED,
+                format!("⚠️ {} mod(s) need DLC that is not installed", counts.missing_dlc),
+            );
+        }
+        if counts.abandoned > 0 {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!("⚠️ {} mod(s) look abandoned", counts.abandoned),
+            );
+        }
+        if counts.not_updated_since_patch > 0 {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!("⚠️ {} mod(s) were not updated since the last game update", counts.not_updated_since_patch),
+            );
+        }
+        let game_version = view.game_version.as_deref();
+        if counts.unsupported > 0 {
+            ui.colored_label(
+                Color32::RED,
+                format!("⚠️ {} mod(s) do not declare support for version {}", counts.unsupported, game_version.unwrap_or("?")),
+            );
+        }
+        if counts.unpopular > 0 {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!("⚠️ {} mod(s) are low-rated or have very few subscribers", counts.unpopular),
+            );
         }
 
-        TableBuilder::new(ui)
+        let mut author_filter = game.author_filter.clone();
+        if !view.authors.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label("Author:");
+                egui::ComboBox::from_id_salt("author_filter")
+                    .selected_text(author_filter.as_deref().map_or("<all>", |id| personas.name_for(id)))
+                    .show_ui(ui, |ui| {
+                        if ui.selectable_label(author_filter.is_none(), "<all>").clicked() {
+                            author_filter = None;
+                        }
+                        for (name, id, count) in &view.authors {
+                            let selected = author_filter.as_deref() == Some(id.as_str());
+                            if ui.selectable_label(selected, format!("{} ({})", name, count)).clicked() {
+                                author_filter = Some(id.clone());
+                            }
+                        }
+                    });
+            });
+        }
+
+        let show_thumbnails = thumbnails.is_some();
+        let row_height = if show_thumbnails { 34.0 } else { 20.0 };
+
+        let mut table = TableBuilder::new(ui)
             .striped(true)
             .resizable(true)
-            .drag_to_scroll(true)
+            .drag_to_scroll(true);
+        if show_thumbnails {
+            table = table.column(Column::exact(36.0)); // Thumbnail
+        }
+        table
             .column(Column::initial(80.0))   // ID
             .column(Column::remainder())    // Name
+            .column(Column::initial(120.0)) // Author
             .column(Column::initial(100.0)) // Status
+            .column(Column::initial(130.0)) // Derived
             .column(Column::remainder())    // Updated
             .column(Column::initial(120.0)) // State
+            .columns(Column::initial(100.0), usize::from(game_version.is_some())) // Game version
+            .column(Column::remainder())    // Requires
+            .columns(Column::initial(140.0), usize::from(popularity.show_column)) // Popularity
             .header(20.0, |mut header| {
+                if show_thumbnails {
+                    header.col(|_| {});
+                }
                 header.col(|ui| {
                     if ui.button("ID").clicked() {
-                        *sort_by_id_asc = !*sort_by_id_asc;
+                        actions.push(Action::ToggleSort);
                     }
                 });
                 header.col(|ui| { ui.heading("Name"); });
+                header.col(|ui| { ui.heading("Author"); });
                 header.col(|ui| { ui.heading("CSV Status"); });
+                header.col(|ui| { ui.heading("Derived"); });
                 header.col(|ui| { ui.heading("Last Updated"); });
                 header.col(|ui| { ui.heading("State"); });
+                if game_version.is_some() {
+                    header.col(|ui| { ui.heading("Version"); });
+                }
+                header.col(|ui| { ui.heading("Requires"); });
+                if popularity.show_column {
+                    header.col(|ui| { ui.heading("Popularity"); });
+                }
             })
-            .body(|mut body| {
-                for m in &mods_sorted {
-                    body.row(20.0, |mut row| {
-                        // ID with hyperlink
+            .body(|body| {
+                let rows = &view.rows;
+                // Only the rows on screen are laid out
+                body.rows(row_height, rows.len(), |mut row| {
+                    let (m, flags) = (&game.mods[rows[row.index()]], &view.flags[rows[row.index()]]);
+                    // Preview thumbnail, only requested once the row is on screen
+                    let mut texture = None;
+                    if let Some((thumbs, rt)) = thumbnails.as_mut() {
                         row.col(|ui| {
-                            let url = format!(
-                                "https://steamcommunity.com/sharedfiles/filedetails/?id={}",
-                                m.id
-                            );
-                            let _ = ui.hyperlink_to(&m.id, url);
+                            if let Some(url) = &m.preview_url
+                                && ui.is_rect_visible(ui.max_rect())
+                            {
+                                texture = thumbs.get(rt, &m.id, url).cloned();
+                            }
+                            if let Some(texture) = &texture {
+                                ui.add(egui::Image::new(texture).fit_to_exact_size(egui::vec2(32.0, 32.0)));
+                            }
                         });
+                    }
 
-                        // Name
-                        row.col(|ui| {
-                            let _ = ui.label(m.name.as_deref().unwrap_or("<unknown>"));
-                        });
+                    // ID with hyperlink
+                    row.col(|ui| {
+                        let url = steam::workshop_url(&m.id);
+                        let _ = ui.hyperlink_to(&m.id, url);
+                    });
 
-                        // CSV Status with color
-                        row.col(|ui| {
-                            if let Some(status) = &m.status {
-                                let color = status_colors.color_for(status);
-                                let _ = ui.colored_label(color, status);
-                            } else {
-                                let _ = ui.label("?");
+                    // Name (click to show details)
+                    row.col(|ui| {
+                        let selected = game.selected_mod.as_deref() == Some(m.id.as_str());
+                        let mut response =
+                            ui.selectable_label(selected, m.name.as_deref().unwrap_or("<unknown>"));
+                        if let Some(texture) = &texture {
+                            response = response.on_hover_ui(|ui| {
+                                ui.add(egui::Image::new(texture).fit_to_exact_size(egui::vec2(128.0, 128.0)));
+                            });
+                        }
+                        if response.clicked() {
+                            actions.push(Action::SelectMod(Some(m.id.clone())));
+                        }
+                    });
+
+                    // Author (click to filter)
+                    row.col(|ui| {
+                        if let Some(creator) = &m.creator {
+                            if ui
+                                .link(personas.name_for(creator))
+                                .on_hover_text("Show only mods by this author")
+                                .clicked()
+                            {
+                                author_filter = Some(creator.clone());
                             }
-                        });
+                        } else {
+                            let _ = ui.label("-");
+                        }
+                    });
 
-                        // Last Updated
-                        row.col(|ui| {
-                            let _ = ui.label(format_timestamp(m.last_updated.as_deref()));
-                        });
+                    // CSV Status with color
+                    row.col(|ui| {
+                        if let Some(status) = &m.status {
+                            let color = status_colors.color_for(status);
+                            let _ = ui.colored_label(color, status);
+                        } else {
+                            let _ = ui.label("?");
+                        }
+                    });
 
-                        // State (outdated check)
-                        row.col(|ui| {
-                            if let Some(outdated) = m.is_outdated() {
-                                if outdated {
-                                    let _ = ui.colored_label(Color32::RED, "⚠️ Outdated");
-                                } else {
-                                    let _ = ui.colored_label(Color32::GREEN, "✅ Up to date");
-                                }
+                    // Derived status from update age, next to the CSV status
+                    row.col(|ui| {
+                        if let Some(status) = &flags.derived {
+                            ui.colored_label(Color32::YELLOW, status.label())
+                                .on_hover_text(status.description());
+                        } else {
+                            let _ = ui.label("-");
+                        }
+                    });
+
+                    // Last Updated
+                    row.col(|ui| {
+                        let _ = ui.label(format_timestamp(m.last_updated.as_deref()));
+                    });
+
+                    // State (outdated check)
+                    row.col(|ui| {
+                        if m.removed {
+                            ui.colored_label(Color32::RED, "❌ Removed")
+                                .on_hover_text("No longer available on the Steam Workshop");
+                        } else if let Some(outdated) = m.is_outdated() {
+                            if outdated {
+                                let _ = ui.colored_label(Color32::RED, "⚠️ Outdated");
                             } else {
+                                let _ = ui.colored_label(Color32::GREEN, "✅ Up to date");
+                            }
+                        } else {
+                            let _ = ui.label("-");
+                        }
+                    });
+
+                    // Declared support for the installed game version
+                    if let Some(version) = game_version {
+                        row.col(|ui| match &flags.version_support {
+                            Some(VersionSupport::Supported) => {
+                                let _ = ui.colored_label(Color32::GREEN, format!("✅ {}", version));
+                            }
+                            Some(VersionSupport::Unsupported(declared)) => {
+                                ui.colored_label(Color32::RED, format!("⚠️ not {}", version))
+                                    .on_hover_text(format!("Declares: {}", declared.join(", ")));
+                            }
+                            Some(VersionSupport::Undeclared) => {
+                                ui.weak("no version tags");
+                            }
+                            None => {
                                 let _ = ui.label("-");
                             }
                         });
+                    }
+
+                    // Requires (missing dependencies)
+                    row.col(|ui| {
+                        let missing = &flags.missing_requirements;
+                        let needs_dlc = &flags.missing_dlc;
+                        if m.requires.is_none() && needs_dlc.is_empty() {
+                            let _ = ui.label("-");
+                        } else if missing.is_empty() && needs_dlc.is_empty() {
+                            let _ = ui.colored_label(Color32::GREEN, "✅");
+                        } else {
+                            ui.horizontal(|ui| {
+                                for appid in needs_dlc {
+                                    let url = format!("https://store.steampowered.com/app/{}", appid);
+                                    let _ = ui.hyperlink_to(
+                                        egui::RichText::new(format!("needs {} (DLC)", dlc.name_for(*appid)))
+                                            .color(Color32::RED),
+                                        url,
+                                    );
+                                }
+                                for id in missing {
+                                    let title = game.dependency_titles.get(id).unwrap_or(id);
+                                    let url = steam::workshop_url(id);
+                                    let _ = ui.hyperlink_to(
+                                        egui::RichText::new(format!("requires {} (not installed)", title))
+                                            .color(Color32::RED),
+                                        url,
+                                    );
+                                }
+                            });
+                        }
                     });
-                }
+
+                    // Popularity: vote score bar, subscribers and favorites
+                    if popularity.show_column {
+                        row.col(|ui| {
+                            let subs = m.subscriptions.map_or("-".to_string(), |s| s.to_string());
+                            let favs = m.favorited.map_or("-".to_string(), |f| f.to_string());
+                            let text = match m.votes {
+                                Some(v) => format!("{:.0}% · {} subs", v.score * 100.0, subs),
+                                None => format!("{} subs", subs),
+                            };
+                            let mut bar = egui::ProgressBar::new(m.votes.map_or(0.0, |v| v.score))
+                                .desired_height(14.0)
+                                .text(text);
+                            if flags.popularity_warning.is_some() {
+                                bar = bar.fill(Color32::from_rgb(160, 110, 0));
+                            }
+                            let mut hover = format!("Subscribers: {}\nFavorites: {}", subs, favs);
+                            if let Some(v) = m.votes {
+                                hover += &format!("\nVotes: 👍 {} / 👎 {}", v.votes_up, v.votes_down);
+                            }
+                            if let Some(warning) = flags.popularity_warning {
+                                hover += &format!("\n⚠️ {}", warning);
+                            }
+                            ui.add(bar).on_hover_text(hover);
+                        });
+                    }
+                });
             });
+
+        if author_filter != game.author_filter {
+            actions.push(Action::SetAuthorFilter(author_filter));
+        }
+        actions
     }
-}
\ No newline at end of file
+}
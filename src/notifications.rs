@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_json, write_json};
+use crate::heuristics::now_unix;
+
+/// Oldest entries are dropped beyond this
+const MAX_HISTORY: usize = 500;
+
+/// A change noticed by a check, e.g. "3 mods updated remotely".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Notification {
+    pub time: u64, // unix seconds
+    pub appid: String,
+    pub game_name: String,
+    pub text: String,
+    /// Names of the affected mods, shown on hover
+    #[serde(default)]
+    pub details: Vec<String>,
+    #[serde(default)]
+    pub read: bool,
+}
+
+/// Notification history, persisted in the data directory.
+#[derive(Default)]
+pub struct Notifications {
+    pub items: Vec<Notification>, // oldest first
+}
+
+impl Notifications {
+    pub fn load() -> Self {
+        Self {
+            items: read_json("notifications.json").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let _ = write_json("notifications.json", &self.items);
+    }
+
+    pub fn push(&mut self, appid: &str, game_name: &str, text: String, details: Vec<String>) {
+        self.items.push(Notification {
+            time: now_unix(),
+            appid: appid.to_string(),
+            game_name: game_name.to_string(),
+            text,
+            details,
+            read: false,
+        });
+        if self.items.len() > MAX_HISTORY {
+            self.items.drain(..self.items.len() - MAX_HISTORY);
+        }
+    }
+
+    /// Report sheet status changes given as (mod title, new status).
+    pub fn push_status_changes(&mut self, appid: &str, game_name: &str, changes: Vec<(String, String)>) {
+        match changes.as_slice() {
+            [] => {}
+            [(title, status)] => {
+                self.push(appid, game_name, format!("Sheet status for {} changed to {}", title, status), Vec::new())
+            }
+            _ => {
+                let text = format!("{} sheet statuses changed", changes.len());
+                let details = changes.into_iter().map(|(title, status)| format!("{}: {}", title, status)).collect();
+                self.push(appid, game_name, text, details);
+            }
+        }
+    }
+
+    pub fn unread(&self) -> usize {
+        self.items.iter().filter(|n| !n.read).count()
+    }
+
+    pub fn unread_for(&self, appid: &str) -> usize {
+        self.items.iter().filter(|n| !n.read && n.appid == appid).count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for n in &mut self.items {
+            n.read = true;
+        }
+    }
+}
+
+/// Changes seen while a fetch is running, reported as notifications once it ends.
+#[derive(Default)]
+pub struct PendingChanges {
+    pub updated: Vec<String>, // mod titles
+    pub removed: Vec<String>,
+}
+
+impl PendingChanges {
+    /// Add one notification per kind of change to the game's history.
+    pub fn report(self, notifications: &mut Notifications, appid: &str, game_name: &str) {
+        match self.updated.len() {
+            0 => {}
+            1 => notifications.push(appid, game_name, format!("{} was updated remotely", self.updated[0]), Vec::new()),
+            n => notifications.push(appid, game_name, format!("{} mods updated remotely", n), self.updated),
+        }
+        for title in self.removed {
+            notifications.push(appid, game_name, format!("{} was removed from the Workshop", title), Vec::new());
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use egui::RichText;
+
+use crate::model::format_timestamp;
+use crate::notifications::Notifications;
+use crate::state::Action;
+
+pub struct NotificationsPanel;
+
+impl NotificationsPanel {
+    /// Window with the history of changes found by checks, newest first.
+    pub fn show(ctx: &egui::Context, notifications: &Notifications, open: &mut bool) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        egui::Window::new("Notifications")
+            .open(open)
+            .resizable(true)
+            .default_size([480.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(notifications.unread() > 0, egui::Button::new("Mark all as read"))
+                        .clicked()
+                    {
+                        actions.push(Action::MarkAllNotificationsRead);
+                    }
+                    if ui
+                        .add_enabled(!notifications.items.is_empty(), egui::Button::new("🗑 Clear history"))
+                        .clicked()
+                    {
+                        actions.push(Action::ClearNotifications);
+                    }
+                });
+                ui.separator();
+
+                if notifications.items.is_empty() {
+                    ui.label(RichText::new("No notifications").weak());
+                    return;
+                }
+
+                egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+                    for (i, n) in notifications.items.iter().enumerate().rev() {
+                        ui.horizontal(|ui| {
+                            let time = format_timestamp(Some(&n.time.to_string()));
+                            ui.label(RichText::new(time).weak());
+                            ui.label(RichText::new(&n.game_name).weak());
+                            let text = if n.read { RichText::new(&n.text) } else { RichText::new(format!("● {}", n.text)).strong() };
+                            let label = ui.label(text);
+                            if !n.details.is_empty() {
+                                label.on_hover_text(n.details.join("\n"));
+                            }
+                            if ui.small_button("Show").on_hover_text("Select this game").clicked() {
+                                actions.push(Action::ShowNotification(i));
+                            }
+                            if !n.read && ui.small_button("✔").on_hover_text("Mark as read").clicked() {
+                                actions.push(Action::MarkNotificationRead(i));
+                            }
+                        });
+                    }
+                });
+            });
+
+        actions
+    }
+}
@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use crate::config::{read_json, write_json};
+
+/// SteamID64 -> persona name, persisted in the data directory.
+#[derive(Default)]
+pub struct PersonaCache {
+    pub names: HashMap<String, String>,
+}
+
+impl PersonaCache {
+    pub fn load() -> Self {
+        Self {
+            names: read_json("persona_cache.json").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let _ = write_json("persona_cache.json", &self.names);
+    }
+
+    /// Persona name if known, otherwise the raw SteamID64.
+    pub fn name_for<'a>(&'a self, steam_id: &'a str) -> &'a str {
+        self.names.get(steam_id).map_or(steam_id, |n| n.as_str())
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use egui::{Color32, RichText};
+use egui_extras::{Column, TableBuilder};
+use tokio::runtime::Runtime;
+
+use crate::state::{GameEntry, SearchState};
+use crate::model::format_timestamp;
+use crate::personas::PersonaCache;
+use crate::steam::{self, SearchSort, WorkshopQuery};
+use crate::thumbnails::Thumbnails;
+
+const PER_PAGE: u32 = 30;
+
+/// Workshop search window for the selected game, backed by `QueryFiles`.
+pub struct SearchPanel {
+    pub open: bool,
+    appid: String,
+    text: String,
+    tags: String, // comma separated
+    sort: SearchSort,
+    page: u32,
+}
+
+impl SearchPanel {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            appid: String::new(),
+            text: String::new(),
+            tags: String::new(),
+            sort: SearchSort::MostPopular,
+            page: 1,
+        }
+    }
+
+    /// Returns a query to run when the user searches or changes page.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        game: &GameEntry,
+        results: &SearchState,
+        has_api_key: bool,
+        mut thumbnails: Option<(&mut Thumbnails, &Runtime)>,
+        personas: &PersonaCache,
+    ) -> Option<WorkshopQuery> {
+        if !self.open {
+            return None;
+        }
+        // Paging starts over for another game
+        if self.appid != game.appid {
+            self.appid = game.appid.clone();
+            self.page = 1;
+        }
+
+        let mut query = None;
+        let mut open = self.open;
+        egui::Window::new(format!("Search Workshop: {}", game.name))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([700.0, 500.0])
+            .show(ctx, |ui| {
+                if !has_api_key {
+                    ui.colored_label(Color32::YELLOW, "Searching needs a Steam Web API key (see Settings).");
+                    return;
+                }
+
+                let mut search = false;
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    let response = ui.text_edit_singleline(&mut self.text);
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        search = true;
+                    }
+                    egui::ComboBox::from_id_salt("search_sort")
+                        .selected_text(self.sort.label())
+                        .show_ui(ui, |ui| {
+                            for sort in SearchSort::ALL {
+                                if ui.selectable_value(&mut self.sort, sort, sort.label()).clicked() {
+                                    search = true;
+                                }
+                            }
+                        });
+                    if ui.button("🔍 Search").clicked() {
+                        search = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Tags:");
+                    ui.add(egui::TextEdit::singleline(&mut self.tags).hint_text("e.g. Mod, 1.5"));
+                });
+                if search {
+                    self.page = 1;
+                    query = Some(self.query());
+                }
+
+                ui.separator();
+
+                let installed: HashSet<&str> = game.mods.iter().map(|m| m.id.as_str()).collect();
+                let mut new_page = None;
+                match results {
+                    SearchState::Idle => {}
+                    SearchState::Loading => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Searching…");
+                        });
+                    }
+                    SearchState::Failed => {
+                        ui.colored_label(Color32::RED, "Search failed");
+                    }
+                    SearchState::Loaded { total, items } => {
+                        let pages = total.div_ceil(PER_PAGE as u64).max(1) as u32;
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(self.page > 1, egui::Button::new("◀")).clicked() {
+                                new_page = Some(self.page - 1);
+                            }
+                            ui.label(format!("Page {} / {} ({} results)", self.page, pages, total));
+                            if ui.add_enabled(self.page < pages, egui::Button::new("▶")).clicked() {
+                                new_page = Some(self.page + 1);
+                            }
+                        });
+
+                        let row_height = if thumbnails.is_some() { 34.0 } else { 20.0 };
+                        let mut table = TableBuilder::new(ui).striped(true).resizable(true);
+                        if thumbnails.is_some() {
+                            table = table.column(Column::exact(36.0));
+                        }
+                        table
+                            .column(Column::remainder()) // Title
+                            .column(Column::initial(120.0)) // Author
+                            .column(Column::initial(120.0)) // Updated
+                            .column(Column::initial(100.0)) // Installed
+                            .header(20.0, |mut header| {
+                                if thumbnails.is_some() {
+                                    header.col(|_| {});
+                                }
+                                header.col(|ui| { ui.heading("Title"); });
+                                header.col(|ui| { ui.heading("Author"); });
+                                header.col(|ui| { ui.heading("Updated"); });
+                                header.col(|ui| { ui.heading("Installed"); });
+                            })
+                            .body(|mut body| {
+                                for item in items {
+                                    body.row(row_height, |mut row| {
+                                        if let Some((thumbs, rt)) = thumbnails.as_mut() {
+                                            row.col(|ui| {
+                                                if let Some(url) = &item.preview_url
+                                                    && ui.is_rect_visible(ui.max_rect())
+                                                    && let Some(texture) = thumbs.get(rt, &item.id, url)
+                                                {
+                                                    ui.add(
+                                                        egui::Image::new(texture)
+                                                            .fit_to_exact_size(egui::vec2(32.0, 32.0)),
+                                                    );
+                                                }
+                                            });
+                                        }
+                                        row.col(|ui| {
+                                            let url = steam::workshop_url(&item.id);
+                                            ui.hyperlink_to(&item.title, url)
+                                                .on_hover_text(item.tags.join(", "));
+                                        });
+                                        row.col(|ui| {
+                                            let author = item.creator.as_deref().map_or("-", |c| personas.name_for(c));
+                                            ui.label(author);
+                                        });
+                                        row.col(|ui| {
+                                            ui.label(format_timestamp(Some(&item.time_updated)));
+                                        });
+                                        row.col(|ui| {
+                                            if installed.contains(item.id.as_str()) {
+                                                ui.colored_label(Color32::GREEN, "✅ Installed");
+                                            } else {
+                                                ui.label(RichText::new("Not installed").weak());
+                                            }
+                                        });
+                                    });
+                                }
+                            });
+                    }
+                }
+
+                if let Some(page) = new_page {
+                    self.page = page;
+                    query = Some(self.query());
+                }
+            });
+        self.open = open;
+
+        query
+    }
+
+    fn query(&self) -> WorkshopQuery {
+        WorkshopQuery {
+            appid: self.appid.clone(),
+            text: self.text.trim().to_string(),
+            tags: self
+                .tags
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+            sort: self.sort,
+            page: self.page,
+            per_page: PER_PAGE,
+        }
+    }
+}
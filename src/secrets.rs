@@ -0,0 +1,38 @@
+use std::{fs, io::Write, path::PathBuf};
+
+/// The Steam Web API key lives in the user's config directory rather than next to the
+/// executable with the other JSON data, so it is not shared along with the data folder.
+fn api_key_path() -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("WorkshopWhisperer");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join("steam_api_key"))
+}
+
+pub fn load_api_key() -> String {
+    api_key_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|key| key.trim().to_string())
+        .unwrap_or_default()
+}
+
+pub fn save_api_key(key: &str) -> std::io::Result<()> {
+    let path = api_key_path()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no config directory"))?;
+    if key.is_empty() {
+        let _ = fs::remove_file(&path);
+        return Ok(());
+    }
+    // Readable by the current user only, from the moment the file is created
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies to new files; tighten one left by an older version
+        if path.exists() {
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(&path)?.write_all(key.trim().as_bytes())
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_json, write_json};
+use crate::model::WorkshopMod;
+
+/// An installed Workshop item as it was when the app was last closed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstalledItem {
+    pub local_updated: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_updated: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// When the session was saved; older files do not have it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<u64>,
+}
+
+/// mod_id -> item, for one game
+pub type SessionItems = HashMap<String, InstalledItem>;
+
+pub fn load_last_session() -> HashMap<String, SessionItems> {
+    read_json("last_session.json").unwrap_or_default()
+}
+
+pub fn save_last_session(sessions: &HashMap<String, SessionItems>) {
+    let _ = write_json("last_session.json", sessions);
+}
+
+/// Installed items of a game, keeping remote times and titles from the previous session
+/// for mods that were not fetched this time.
+pub fn session_items(mods: &[WorkshopMod], previous: Option<&SessionItems>, now: u64) -> SessionItems {
+    mods.iter()
+        .filter_map(|m| {
+            let prev = previous.and_then(|p| p.get(&m.id));
+            let item = InstalledItem {
+                local_updated: m.local_updated.as_deref()?.parse().ok()?,
+                remote_updated: parse_time(m.last_updated.as_deref()).or_else(|| prev.and_then(|p| p.remote_updated)),
+                title: m.name.clone().or_else(|| prev.and_then(|p| p.title.clone())),
+                saved_at: Some(now),
+            };
+            Some((m.id.clone(), item))
+        })
+        .collect()
+}
+
+fn parse_time(raw: Option<&str>) -> Option<u64> {
+    raw?.parse().ok()
+}
+
+/// What happened to a game's mods while the app was closed.
+#[derive(Clone, Debug, Default)]
+pub struct SessionDiff {
+    pub installed: Vec<String>,
+    pub removed: Vec<(String, String)>, // (id, title from the previous session)
+    pub updated: Vec<String>,           // local copy changed
+    /// Updated on the Workshop since the last session, but Steam has not downloaded it yet
+    pub not_downloaded: Vec<String>,
+}
+
+impl SessionDiff {
+    pub fn compute(previous: &SessionItems, mods: &[WorkshopMod]) -> Self {
+        let mut diff = SessionDiff::default();
+        for m in mods {
+            let local = parse_time(m.local_updated.as_deref());
+            let Some(prev) = previous.get(&m.id) else {
+                diff.installed.push(m.id.clone());
+                continue;
+            };
+            if local.is_some_and(|local| local != prev.local_updated) {
+                diff.updated.push(m.id.clone());
+            }
+            // Remote times are only known once details were fetched. Without one from the last
+            // session, only updates made after it was saved count as new
+            let newer_than_last_session = |remote: u64| match prev.remote_updated {
+                Some(r) => remote > r,
+                None => prev.saved_at.is_some_and(|saved| remote > saved),
+            };
+            if let (Some(local), Some(remote)) = (local, parse_time(m.last_updated.as_deref()))
+                && remote > local
+                && newer_than_last_session(remote)
+            {
+                diff.not_downloaded.push(m.id.clone());
+            }
+        }
+        for (id, prev) in previous {
+            if !mods.iter().any(|m| &m.id == id) {
+                diff.removed.push((id.clone(), prev.title.clone().unwrap_or_else(|| id.clone())));
+            }
+        }
+        diff.removed.sort();
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.installed.is_empty() && self.removed.is_empty() && self.updated.is_empty() && self.not_downloaded.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(id: &str, local: u64, remote: Option<u64>) -> WorkshopMod {
+        WorkshopMod {
+            id: id.to_string(),
+            local_updated: Some(local.to_string()),
+            last_updated: remote.map(|r| r.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn item(local_updated: u64, remote_updated: Option<u64>, saved_at: Option<u64>) -> InstalledItem {
+        InstalledItem { local_updated, remote_updated, title: None, saved_at }
+    }
+
+    #[test]
+    fn lists_installed_removed_and_updated_mods() {
+        let previous = SessionItems::from([
+            ("1".to_string(), item(100, None, Some(1_000))),
+            ("2".to_string(), item(100, None, Some(1_000))),
+            ("3".to_string(), InstalledItem { title: Some("Gone".to_string()), ..item(100, None, Some(1_000)) }),
+        ]);
+        let mods = [installed("1", 100, None), installed("2", 150, None), installed("4", 100, None)];
+
+        let diff = SessionDiff::compute(&previous, &mods);
+        assert_eq!(diff.installed, ["4"]);
+        assert_eq!(diff.removed, [("3".to_string(), "Gone".to_string())]);
+        assert_eq!(diff.updated, ["2"]);
+        assert!(diff.not_downloaded.is_empty());
+        assert!(SessionDiff::compute(&previous, &mods[..2]).installed.is_empty());
+    }
+
+    #[test]
+    fn only_remote_updates_since_the_last_session_are_not_downloaded() {
+        let previous = SessionItems::from([
+            ("1".to_string(), item(100, Some(200), Some(1_000))), // known outdated already
+            ("2".to_string(), item(100, Some(200), Some(1_000))),
+            ("3".to_string(), item(100, None, Some(1_000))), // never fetched, outdated for long
+            ("4".to_string(), item(100, None, Some(1_000))),
+            ("5".to_string(), item(100, None, None)), // saved by an older version
+        ]);
+        let mods = [
+            installed("1", 100, Some(200)),
+            installed("2", 100, Some(300)),
+            installed("3", 100, Some(500)),
+            installed("4", 100, Some(1_500)),
+            installed("5", 100, Some(1_500)),
+        ];
+
+        let diff = SessionDiff::compute(&previous, &mods);
+        assert_eq!(diff.not_downloaded, ["2", "4"]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn nothing_changed_is_empty() {
+        let previous = SessionItems::from([("1".to_string(), item(100, Some(100), Some(1_000)))]);
+        assert!(SessionDiff::compute(&previous, &[installed("1", 100, Some(100))]).is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+use egui::{Color32, RichText, Ui};
+
+use crate::state::GameEntry;
+
+pub struct SessionSummary;
+
+impl SessionSummary {
+    /// Window listing what changed in each game since the app was last closed.
+    /// Returns the index of the game to select, if one was clicked.
+    pub fn show(ctx: &egui::Context, games: &[GameEntry], open: &mut bool) -> Option<usize> {
+        let mut selected = None;
+        let mut dismissed = false;
+
+        egui::Window::new("Since last session")
+            .open(open)
+            .resizable(true)
+            .default_size([460.0, 320.0])
+            .show(ctx, |ui| {
+                let changed: Vec<(usize, &GameEntry)> = games
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, g)| g.session_diff.as_ref().is_some_and(|d| !d.is_empty()))
+                    .collect();
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    if changed.is_empty() {
+                        ui.label(RichText::new("No changes since the last session").weak());
+                    }
+                    for (i, game) in changed {
+                        let Some(diff) = &game.session_diff else {
+                            continue;
+                        };
+                        if ui.link(RichText::new(&game.name).strong()).on_hover_text("Show this game").clicked() {
+                            selected = Some(i);
+                        }
+                        let titles = |ids: &[String]| ids.iter().map(|id| game.title_for(id)).collect::<Vec<_>>();
+                        Self::list(ui, "🆕 Newly installed", Color32::LIGHT_BLUE, titles(&diff.installed));
+                        Self::list(
+                            ui,
+                            "🗑 Removed",
+                            Color32::GRAY,
+                            diff.removed.iter().map(|(_, title)| title.clone()).collect(),
+                        );
+                        Self::list(ui, "⬇ Updated locally", Color32::GREEN, titles(&diff.updated));
+                        Self::list(ui, "⏳ Updated on the Workshop, not downloaded yet", Color32::YELLOW, titles(&diff.not_downloaded));
+                        ui.add_space(6.0);
+                    }
+                });
+
+                ui.separator();
+                if ui.button("Dismiss").clicked() {
+                    dismissed = true;
+                }
+            });
+
+        if dismissed {
+            *open = false;
+        }
+        selected
+    }
+
+    fn list(ui: &mut Ui, label: &str, color: Color32, titles: Vec<String>) {
+        if titles.is_empty() {
+            return;
+        }
+        ui.colored_label(color, format!("{} ({})", label, titles.len()))
+            .on_hover_text(titles.join("\n"));
+        ui.label(RichText::new(titles.join(", ")).weak());
+    }
+}
@@ -1,36 +1,56 @@
 use egui::{Color32, RichText};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::config::{BrokenStatuses, GeneralConfig};
+use crate::state::ApiKeyStatus;
 
 pub enum SettingsAction {
     None,
     SteamPathChanged,
+    GeneralChanged,
+    ApiKeyChanged,
+    ApiKeyEdited,
+    TestApiKey,
+    BrokenStatusesChanged(BTreeSet<String>),
 }
 
 pub struct SettingsWindow {
     pub open: bool,
+    show_api_key: bool,
+    api_key_edited: bool, // typed into since the key was last saved
     editing_colors: HashMap<String, Color32>,
+    editing_broken: BTreeSet<String>,
     new_term: String,
     new_color: Color32,
+    new_broken: bool,
 }
 
 impl SettingsWindow {
     pub fn new() -> Self {
         Self {
             open: false,
+            show_api_key: false,
+            api_key_edited: false,
             editing_colors: HashMap::new(),
+            editing_broken: BTreeSet::new(),
             new_term: String::new(),
             new_color: Color32::WHITE,
+            new_broken: false,
         }
     }
 
-    pub fn load_from(&mut self, status_colors: &crate::status_colors::StatusColors) {
+    pub fn load_from(&mut self, status_colors: &crate::status_colors::StatusColors, broken: &BrokenStatuses) {
         self.editing_colors = status_colors.known.clone();
+        self.editing_broken = broken.terms.clone();
     }
 
     pub fn show(
         &mut self,
         ctx: &egui::Context,
         user_path: &mut String,
+        general: &mut GeneralConfig,
+        api_key: &mut String,
+        api_key_status: &ApiKeyStatus,
         status_colors: &mut crate::status_colors::StatusColors,
     ) -> SettingsAction {
         let mut action = SettingsAction::None;
@@ -48,17 +68,128 @@ impl SettingsWindow {
 
                             ui.horizontal(|ui| {
                                 ui.label("Steam Path:");
-                                if ui.button("Change…").clicked() {
-                                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                                        *user_path = path.display().to_string();
-                                        action = SettingsAction::SteamPathChanged;
-                                    }
+                                if ui.button("Change…").clicked()
+                                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                                {
+                                    *user_path = path.display().to_string();
+                                    action = SettingsAction::SteamPathChanged;
                                 }
                                 if !user_path.is_empty() {
                                     ui.label(user_path.clone());
                                 }
                             });
 
+                            ui.horizontal(|ui| {
+                                ui.label("Parallel Workshop batches:");
+                                if ui
+                                    .add(egui::DragValue::new(&mut general.fetch_concurrency).range(1..=16))
+                                    .changed()
+                                {
+                                    action = SettingsAction::GeneralChanged;
+                                }
+                            });
+
+                            if ui
+                                .checkbox(&mut general.show_thumbnails, "Download Workshop preview thumbnails")
+                                .on_hover_text("Turn off for offline or metered connections")
+                                .changed()
+                            {
+                                action = SettingsAction::GeneralChanged;
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label("Check all games every");
+                                if ui
+                                    .add(egui::DragValue::new(&mut general.check_interval_minutes).range(0..=1440))
+                                    .changed()
+                                {
+                                    action = SettingsAction::GeneralChanged;
+                                }
+                                ui.label("minutes (0 = off)");
+                            })
+                            .response
+                            .on_hover_text("Rescans installed mods and fetches Workshop details while the app is open");
+
+                            ui.horizontal(|ui| {
+                                ui.label("Steam Web API key:");
+                                let field = egui::TextEdit::singleline(api_key)
+                                    .password(!self.show_api_key)
+                                    .desired_width(260.0);
+                                let response = ui.add(field);
+                                if response.changed() {
+                                    self.api_key_edited = true;
+                                    action = SettingsAction::ApiKeyEdited;
+                                }
+                                // Saved once editing is done rather than on every keystroke
+                                if response.lost_focus() && self.api_key_edited {
+                                    self.api_key_edited = false;
+                                    action = SettingsAction::ApiKeyChanged;
+                                }
+                                ui.toggle_value(&mut self.show_api_key, "👁")
+                                    .on_hover_text("Show key");
+                                let checking = matches!(api_key_status, ApiKeyStatus::Checking);
+                                if ui
+                                    .add_enabled(!api_key.is_empty() && !checking, egui::Button::new("Test key"))
+                                    .clicked()
+                                {
+                                    self.api_key_edited = false;
+                                    action = SettingsAction::TestApiKey;
+                                }
+                                match api_key_status {
+                                    ApiKeyStatus::Unknown => {}
+                                    ApiKeyStatus::Checking => {
+                                        ui.spinner();
+                                    }
+                                    ApiKeyStatus::Valid => {
+                                        ui.colored_label(Color32::GREEN, "✅ Key works");
+                                    }
+                                    ApiKeyStatus::Invalid => {
+                                        ui.colored_label(Color32::RED, "❌ Key was rejected");
+                                    }
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Steam account (SteamID64):");
+                                let field = egui::TextEdit::singleline(&mut general.steam_id)
+                                    .hint_text("detect from last login")
+                                    .desired_width(180.0);
+                                if ui.add(field).changed() {
+                                    action = SettingsAction::GeneralChanged;
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Needed for author names and other authenticated features.");
+                                ui.hyperlink_to("Get a key", "https://steamcommunity.com/dev/apikey");
+                            });
+
+                            ui.separator();
+                            ui.heading("Popularity");
+
+                            if ui
+                                .checkbox(&mut general.popularity.show_column, "Show popularity column")
+                                .changed()
+                            {
+                                action = SettingsAction::GeneralChanged;
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Warn below vote score:");
+                                let mut percent = general.popularity.min_score * 100.0;
+                                if ui
+                                    .add(egui::DragValue::new(&mut percent).range(0.0..=100.0).suffix("%"))
+                                    .changed()
+                                {
+                                    general.popularity.min_score = percent / 100.0;
+                                    action = SettingsAction::GeneralChanged;
+                                }
+                                ui.label("or subscribers:");
+                                if ui
+                                    .add(egui::DragValue::new(&mut general.popularity.min_subscribers))
+                                    .changed()
+                                {
+                                    action = SettingsAction::GeneralChanged;
+                                }
+                            });
+
                             ui.separator();
                             ui.heading("Status Colors");
 
@@ -69,14 +200,38 @@ impl SettingsWindow {
                                 .show(ui, |ui| {
                                     ui.label(RichText::new("Status").strong());
                                     ui.label(RichText::new("Color").strong());
+                                    ui.label(RichText::new("Broken").strong())
+                                        .on_hover_text("Counted as a problem by the dashboard, history and the check command");
                                     ui.label(""); // delete column
                                     ui.end_row();
 
-                                    for (term, color) in &mut self.editing_colors {
-                                        ui.label(term);
-                                        ui.color_edit_button_srgba(color);
+                                    // Broken statuses may have no colour of their own
+                                    let terms: BTreeSet<String> = self
+                                        .editing_colors
+                                        .keys()
+                                        .chain(&self.editing_broken)
+                                        .cloned()
+                                        .collect();
+                                    for term in terms {
+                                        ui.label(&term);
+                                        match self.editing_colors.get_mut(&term) {
+                                            Some(color) => {
+                                                ui.color_edit_button_srgba(color);
+                                            }
+                                            None => {
+                                                ui.label("-");
+                                            }
+                                        }
+                                        let mut broken = self.editing_broken.contains(&term);
+                                        if ui.checkbox(&mut broken, "").changed() {
+                                            if broken {
+                                                self.editing_broken.insert(term.clone());
+                                            } else {
+                                                self.editing_broken.remove(&term);
+                                            }
+                                        }
                                         if ui.button("❌").clicked() {
-                                            to_remove = Some(term.clone());
+                                            to_remove = Some(term);
                                         }
                                         ui.end_row();
                                     }
@@ -84,6 +239,7 @@ impl SettingsWindow {
 
                             if let Some(key) = to_remove {
                                 self.editing_colors.remove(&key);
+                                self.editing_broken.remove(&key);
                             }
 
                             ui.separator();
@@ -95,14 +251,20 @@ impl SettingsWindow {
                                         .desired_width(160.0),
                                 );
                                 ui.color_edit_button_srgba(&mut self.new_color);
+                                ui.checkbox(&mut self.new_broken, "Broken");
 
-                                if ui.button("➕ Add Status").clicked() {
-                                    if !self.new_term.is_empty() {
-                                        self.editing_colors
-                                            .insert(self.new_term.clone(), self.new_color);
-                                        self.new_term.clear();
-                                        self.new_color = Color32::WHITE;
+                                if ui.button("➕ Add Status").clicked()
+                                    && !self.new_term.is_empty()
+                                {
+                                    // Statuses are matched case-insensitively
+                                    let term = self.new_term.trim().to_lowercase();
+                                    self.editing_colors.insert(term.clone(), self.new_color);
+                                    if self.new_broken {
+                                        self.editing_broken.insert(term);
                                     }
+                                    self.new_term.clear();
+                                    self.new_color = Color32::WHITE;
+                                    self.new_broken = false;
                                 }
                             });
 
@@ -111,6 +273,7 @@ impl SettingsWindow {
                             if ui.button(RichText::new("💾 Save Status Colors").strong()).clicked() {
                                 status_colors.known = self.editing_colors.clone();
                                 status_colors.save();
+                                action = SettingsAction::BrokenStatusesChanged(self.editing_broken.clone());
                             }
 
                             ui.separator();
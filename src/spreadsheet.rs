@@ -1,5 +1,9 @@
 use reqwest::blocking;
 use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::{SpreadsheetConfig, read_json, write_json};
 
 /// Convert a Google Sheets link into a CSV export URL.
 /// Works with links ending in /edit, /view, /copy, etc.
@@ -35,4 +39,83 @@ pub fn load_csv_from_file(path: &str) -> Option<Vec<Vec<String>>> {
         rows.push(record.iter().map(|s| s.to_string()).collect());
     }
     Some(rows)
-}
\ No newline at end of file
+}
+/// Per-mod status (and optional name) from the sheet, using the configured header row and columns.
+/// Returns None until the header row, ID and status columns are chosen.
+pub fn sheet_statuses(
+    rows: &[Vec<String>],
+    cfg: &SpreadsheetConfig,
+) -> Option<HashMap<String, (String, Option<String>)>> {
+    let (header_idx, id_col, status_col) = (cfg.header_row_index?, cfg.id_col?, cfg.status_col?);
+    if header_idx >= rows.len() {
+        return None;
+    }
+
+    let mut statuses = HashMap::new();
+    for row in rows.iter().skip(header_idx + 1) {
+        if let (Some(id), Some(status)) = (row.get(id_col), row.get(status_col)) {
+            let name = cfg.name_col.and_then(|c| row.get(c)).cloned();
+            statuses.insert(id.clone(), (status.clone(), name));
+        }
+    }
+    Some(statuses)
+}
+
+/// Where a game's spreadsheet is loaded from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SheetSource {
+    Url(String), // Google Sheets link
+    File(String),
+}
+
+impl SheetSource {
+    /// The configured source; a sheet link takes precedence over a CSV file.
+    pub fn from_config(cfg: &SpreadsheetConfig) -> Option<Self> {
+        if !cfg.sheet_url.is_empty() {
+            Some(SheetSource::Url(cfg.sheet_url.clone()))
+        } else {
+            cfg.sheet_file.clone().map(SheetSource::File)
+        }
+    }
+
+    /// The link or path, used to tell whether a cached copy belongs to this source.
+    pub fn key(&self) -> &str {
+        match self {
+            SheetSource::Url(url) => url,
+            SheetSource::File(path) => path,
+        }
+    }
+
+    /// Blocking; run it off the UI thread.
+    pub fn load(&self) -> Result<Vec<Vec<String>>, String> {
+        match self {
+            SheetSource::Url(url) => {
+                let csv_url = to_csv_url(url).ok_or("Not a Google Sheets link")?;
+                load_csv_from_url(&csv_url).ok_or_else(|| "Could not download the spreadsheet".to_string())
+            }
+            SheetSource::File(path) => {
+                load_csv_from_file(path).ok_or_else(|| format!("Could not read {}", path))
+            }
+        }
+    }
+}
+
+/// Last good copy of a game's sheet, shown while reloading and when loading fails.
+#[derive(Serialize, Deserialize)]
+pub struct CachedSheet {
+    pub source: String,
+    pub saved_at: u64, // unix time
+    pub rows: Vec<Vec<String>>,
+}
+
+pub fn load_cached_sheet(appid: &str, source: &SheetSource) -> Option<CachedSheet> {
+    read_json::<CachedSheet>(&format!("sheet_cache_{}.json", appid)).filter(|c| c.source == source.key())
+}
+
+pub fn save_cached_sheet(appid: &str, source: &SheetSource, rows: &[Vec<String>]) {
+    let saved_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let cached = CachedSheet { source: source.key().to_string(), saved_at, rows: rows.to_vec() };
+    let _ = write_json(&format!("sheet_cache_{}.json", appid), &cached);
+}
@@ -1,48 +1,66 @@
-use egui::Ui;
-use crate::{app::GameEntry, spreadsheet};
+use egui::{Color32, Ui};
+use crate::{state::{Action, GameEntry, SheetStatus}, model::format_timestamp, spreadsheet::{self, SheetSource}};
 
 pub struct SpreadsheetSection;
 
 impl SpreadsheetSection {
-    pub fn ui(game: &mut GameEntry, ui: &mut Ui) {
+    /// Returns the sheet edits and the source to load when the user picks a sheet link or file.
+    pub fn ui(game: &GameEntry, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        // Status stays visible while the settings below are collapsed
+        let cached = game
+            .sheet_cached_at
+            .map(|t| format!("showing the cached copy from {}", format_timestamp(Some(&t.to_string()))));
+        match &game.sheet_status {
+            SheetStatus::NotLoaded | SheetStatus::Loaded => {}
+            SheetStatus::Loading => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Loading spreadsheet…");
+                    if let Some(cached) = &cached {
+                        ui.weak(format!("({})", cached));
+                    }
+                });
+            }
+            SheetStatus::Failed(error) => {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::RED, format!("Could not load the spreadsheet: {}", error));
+                    if ui.button("Retry").clicked() {
+                        actions.extend(SheetSource::from_config(&game.spreadsheet).map(Action::LoadSheet));
+                    }
+                });
+                if let Some(cached) = &cached {
+                    ui.colored_label(Color32::YELLOW, format!("Still {}", cached));
+                }
+            }
+        }
         egui::CollapsingHeader::new("Spreadsheet/CSV Data Settings")
             .default_open(false)
             .show(ui, |ui| {
                 // Load from URL
                 ui.horizontal(|ui| {
                     ui.label("Spreadsheet link:");
-                    ui.text_edit_singleline(&mut game.spreadsheet.sheet_url);
-                    if ui.button("Load").clicked() {
-                        if let Some(csv_url) = spreadsheet::to_csv_url(&game.spreadsheet.sheet_url) {
-                            game.sheet_data = spreadsheet::load_csv_from_url(&csv_url);
-                            if game.spreadsheet.header_row_index.is_none() {
-                                game.spreadsheet.id_col = None;
-                                game.spreadsheet.status_col = None;
-                                game.spreadsheet.name_col = None;
-                            }
-                            game.apply_spreadsheet();
-                        }
+                    let mut url = game.spreadsheet.sheet_url.clone();
+                    if ui.text_edit_singleline(&mut url).changed() {
+                        actions.push(Action::SetSheetUrl(url.clone()));
+                    }
+                    if ui.button("Load").clicked()
+                        && spreadsheet::to_csv_url(&url).is_some()
+                    {
+                        actions.push(Action::LoadSheet(SheetSource::Url(url)));
                     }
                 });
 
                 // Load from file
                 ui.horizontal(|ui| {
-                    if ui.button("Choose CSV file…").clicked() {
-                        if let Some(path) = rfd::FileDialog::new()
+                    if ui.button("Choose CSV file…").clicked()
+                        && let Some(path) = rfd::FileDialog::new()
                             .add_filter("CSV files", &["csv"])
                             .pick_file()
-                        {
-                            if let Some(path_str) = path.to_str() {
-                                game.spreadsheet.sheet_file = Some(path_str.to_string());
-                                game.sheet_data = spreadsheet::load_csv_from_file(path_str);
-                                if game.spreadsheet.header_row_index.is_none() {
-                                    game.spreadsheet.id_col = None;
-                                    game.spreadsheet.status_col = None;
-                                    game.spreadsheet.name_col = None;
-                                }
-                                game.apply_spreadsheet();
-                            }
-                        }
+                        && let Some(path_str) = path.to_str()
+                    {
+                        actions.push(Action::LoadSheet(SheetSource::File(path_str.to_string())));
                     }
 
                     if let Some(path) = &game.spreadsheet.sheet_file {
@@ -55,12 +73,6 @@ impl SpreadsheetSection {
                     if rows.is_empty() {
                         ui.label("Spreadsheet loaded but empty");
                     } else {
-                        // 🔹 Track changes instead of mutating immediately
-                        let mut header_changed = None;
-                        let mut id_col_changed = None;
-                        let mut status_col_changed = None;
-                        let mut name_col_changed = None;
-
                         // Let user pick header row (show first 5 rows)
                         for (ri, row) in rows.iter().take(5).enumerate() {
                             ui.horizontal(|ui| {
@@ -69,7 +81,7 @@ impl SpreadsheetSection {
                                     ui.label(cell);
                                 }
                                 if ui.button("Use as header").clicked() {
-                                    header_changed = Some(ri);
+                                    actions.push(Action::SetHeaderRow(ri));
                                 }
                             });
                         }
@@ -90,7 +102,7 @@ impl SpreadsheetSection {
                                             .selectable_label(game.spreadsheet.id_col == Some(ci), h)
                                             .clicked()
                                         {
-                                            id_col_changed = Some(ci);
+                                            actions.push(Action::SetIdColumn(ci));
                                         }
                                     }
                                 });
@@ -110,7 +122,7 @@ impl SpreadsheetSection {
                                             )
                                             .clicked()
                                         {
-                                            status_col_changed = Some(ci);
+                                            actions.push(Action::SetStatusColumn(ci));
                                         }
                                     }
                                 });
@@ -130,35 +142,15 @@ impl SpreadsheetSection {
                                             )
                                             .clicked()
                                         {
-                                            name_col_changed = Some(ci);
+                                            actions.push(Action::SetNameColumn(ci));
                                         }
                                     }
                                 });
                         }
-
-                        // 🔹 Apply changes after UI borrows are done
-                        if let Some(new_header) = header_changed {
-                            game.spreadsheet.header_row_index = Some(new_header);
-                        }
-                        if let Some(new_id) = id_col_changed {
-                            game.spreadsheet.id_col = Some(new_id);
-                        }
-                        if let Some(new_status) = status_col_changed {
-                            game.spreadsheet.status_col = Some(new_status);
-                        }
-                        if let Some(new_name) = name_col_changed {
-                            game.spreadsheet.name_col = Some(new_name);
-                        }
-
-                        if header_changed.is_some()
-                            || id_col_changed.is_some()
-                            || status_col_changed.is_some()
-                            || name_col_changed.is_some()
-                        {
-                            game.apply_spreadsheet();
-                        }
                     }
                 }
             });
+
+        actions
     }
-}
\ No newline at end of file
+}
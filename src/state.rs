@@ -0,0 +1,1414 @@
+//! Application state without any egui types. Views emit `Action`s, `AppState::update`
+//! applies them and returns the `Effect`s (background jobs, saving) the app carries out.
+//! Job results come back through `AppState::handle_job`, other request results as `Action`s.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::changelog::{CachedChangelog, ChangeNote, ChangelogCache};
+use crate::config::{BrokenStatuses, GameConfig, PopularityConfig, SpreadsheetConfig};
+use crate::dependency_graph::DependencyGraph;
+use crate::dlc::DlcCache;
+use crate::health::GameHealth;
+use crate::heuristics::{self, DerivedStatus, VersionSupport};
+use crate::history::History;
+use crate::jobs::{JobKind, JobMessage};
+use crate::model::WorkshopMod;
+use crate::notifications::{Notifications, PendingChanges};
+use crate::personas::PersonaCache;
+use crate::session::{self, SessionDiff, SessionItems};
+use crate::spreadsheet::{self, SheetSource};
+use crate::steam;
+use crate::subscriptions::SubscriptionDiff;
+use crate::watchlist::WatchedItem;
+
+pub enum SheetStatus {
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+pub enum CollectionState {
+    Empty,
+    Loading,
+    Loaded(Vec<(String, String)>), // (id, title)
+    Failed,
+}
+
+pub enum SubscriptionState {
+    Empty,
+    Loading,
+    Loaded(SubscriptionDiff),
+    Failed,
+}
+
+/// Workshop search of the selected game.
+pub enum SearchState {
+    Idle,
+    Loading,
+    Loaded { total: u64, items: Vec<steam::ItemDetails> },
+    Failed,
+}
+
+/// Outcome of the settings window's key check.
+pub enum ApiKeyStatus {
+    Unknown,
+    Checking,
+    Valid,
+    Invalid,
+}
+
+pub struct GameEntry {
+    pub appid: String,
+    pub name: String,
+    pub path: String,
+    pub manifest: Option<steam::AppManifest>,
+    pub settings: GameConfig,
+    pub version_key_input: String, // build ID or branch for a new version mapping
+
+    pub spreadsheet: SpreadsheetConfig,
+    pub sheet_data: Option<Vec<Vec<String>>>,
+    pub sheet_status: SheetStatus,
+    pub sheet_cached_at: Option<u64>, // set while `sheet_data` is the cached copy
+    sheet_index: Option<HashMap<String, (String, Option<String>)>>, // mod_id -> (status, name)
+    pub mods: Vec<WorkshopMod>,
+    mods_revision: u64, // bumped when mods are added, removed or get new details, sheet data or DLC info
+    pub mods_view: ModsView,
+
+    pub collection_input: String,
+    pub collection: CollectionState,
+
+    pub dependency_titles: HashMap<String, String>, // titles of required items that are not installed
+    pub selected_mod: Option<String>,
+    pub author_filter: Option<String>,
+    pub subscriptions: SubscriptionState,
+    pub watchlist: Vec<WatchedItem>,
+    pub watch_input: String,
+    pub history_since: String, // date of the "what changed" report
+    pub session_diff: Option<SessionDiff>, // None if the game was not seen in the last session
+    pub health: GameHealth, // dashboard counts, see `refresh_health`
+    health_key: Option<(u64, BrokenStatuses)>, // (mods revision, broken statuses) `health` was counted for
+}
+
+impl GameEntry {
+    /// A game with its installed mods as (id, local update time).
+    pub fn new(appid: String, name: String, path: String, local_mods: HashMap<String, u64>) -> Self {
+        let mods = local_mods
+            .into_iter()
+            .map(|(id, local_time)| WorkshopMod {
+                id,
+                local_updated: Some(local_time.to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        Self {
+            appid,
+            name,
+            path,
+            manifest: None,
+            settings: GameConfig::default(),
+            version_key_input: String::new(),
+            spreadsheet: SpreadsheetConfig::default(),
+            sheet_data: None,
+            sheet_status: SheetStatus::NotLoaded,
+            sheet_cached_at: None,
+            sheet_index: None,
+            mods,
+            mods_revision: 0,
+            mods_view: ModsView::default(),
+            collection_input: String::new(),
+            collection: CollectionState::Empty,
+            dependency_titles: HashMap::new(),
+            selected_mod: None,
+            author_filter: None,
+            subscriptions: SubscriptionState::Empty,
+            watchlist: Vec::new(),
+            watch_input: String::new(),
+            history_since: String::new(),
+            session_diff: None,
+            health: GameHealth::default(),
+            health_key: None,
+        }
+    }
+
+    /// Best known display name for a Workshop item, installed or not.
+    pub fn title_for(&self, id: &str) -> String {
+        self.mods
+            .iter()
+            .find(|m| m.id == id)
+            .and_then(|m| m.name.clone())
+            .or_else(|| self.dependency_titles.get(id).cloned())
+            .filter(|t| !t.is_empty())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Replace the mod list with a fresh scan, keeping fetched data for mods still installed.
+    pub fn apply_scan(&mut self, local_mods: HashMap<String, u64>) {
+        let mut existing: HashMap<String, WorkshopMod> =
+            self.mods.drain(..).map(|m| (m.id.clone(), m)).collect();
+
+        self.mods = local_mods
+            .into_iter()
+            .map(|(id, local_time)| {
+                if let Some(mut m) = existing.remove(&id) {
+                    m.local_updated = Some(local_time.to_string());
+                    m
+                } else {
+                    WorkshopMod {
+                        id,
+                        local_updated: Some(local_time.to_string()),
+                        ..Default::default()
+                    }
+                }
+            })
+            .collect();
+        self.mods_revision += 1;
+    }
+
+    pub fn update_session_diff(&mut self, last_session: &HashMap<String, SessionItems>) {
+        self.session_diff = last_session.get(&self.appid).map(|prev| SessionDiff::compute(prev, &self.mods));
+    }
+
+    /// Use newly loaded sheet rows and match them against the mods.
+    pub fn set_sheet_data(&mut self, rows: Vec<Vec<String>>) {
+        self.sheet_data = Some(rows);
+        self.reindex_sheet();
+    }
+
+    /// Rebuild the sheet index after the rows or the column mapping changed.
+    pub fn reindex_sheet(&mut self) {
+        self.sheet_index = self
+            .sheet_data
+            .as_ref()
+            .and_then(|rows| spreadsheet::sheet_statuses(rows, &self.spreadsheet));
+        self.apply_spreadsheet();
+    }
+
+    /// Copy statuses from the sheet index onto the mods.
+    pub fn apply_spreadsheet(&mut self) {
+        if let Some(statuses) = &self.sheet_index {
+            for m in &mut self.mods {
+                m.apply_sheet(statuses);
+            }
+            self.mods_revision += 1;
+        }
+    }
+
+    /// Recount the dashboard problems if the mods or the broken statuses changed since the last count.
+    pub fn refresh_health(&mut self, broken: &BrokenStatuses) {
+        let key = (self.mods_revision, broken.clone());
+        if self.health_key.as_ref() != Some(&key) {
+            self.health = GameHealth::of(self, broken);
+            self.health_key = Some(key);
+        }
+    }
+
+    /// Bring the table rows and the derived data up to date; each part is only rebuilt
+    /// when the mods or one of its other inputs changed.
+    pub fn refresh_mods_view(
+        &mut self,
+        ascending: bool,
+        personas: &PersonaCache,
+        dlc: &DlcCache,
+        popularity: &PopularityConfig,
+        now: u64,
+    ) {
+        let view = &mut self.mods_view;
+        let rows_key = (self.mods_revision, self.author_filter.clone(), ascending);
+        if view.rows_key.as_ref() != Some(&rows_key) {
+            let filter = self.author_filter.as_deref();
+            view.rows = (0..self.mods.len())
+                .filter(|&i| filter.is_none() || self.mods[i].creator.as_deref() == filter)
+                .collect();
+            view.rows.sort_by(|&a, &b| self.mods[a].id.cmp(&self.mods[b].id));
+            if !ascending {
+                view.rows.reverse();
+            }
+            view.rows_key = Some(rows_key);
+        }
+
+        // Abandonment is counted in days, so the hour is precise enough
+        let data_key = (self.mods_revision, self.settings.clone(), popularity.clone(), now / 3600);
+        if view.data_key.as_ref() == Some(&data_key) {
+            return;
+        }
+        let installed: HashSet<&str> = self.mods.iter().map(|m| m.id.as_str()).collect();
+        let manifest = self.manifest.as_ref();
+        let game_version = heuristics::installed_version(manifest, &self.settings);
+        view.flags = self
+            .mods
+            .iter()
+            .map(|m| ModFlags {
+                missing_requirements: m.missing_requirements(&installed).into_iter().map(str::to_string).collect(),
+                missing_dlc: dlc.missing_dlc(&m.id, &self.appid, manifest),
+                derived: heuristics::derive_status(m, manifest, &self.settings, now),
+                version_support: game_version.and_then(|version| heuristics::version_support(m, version)),
+                popularity_warning: m.popularity_warning(popularity),
+            })
+            .collect();
+        view.counts = ModCounts::of(&view.flags);
+        view.game_version = game_version.map(str::to_string);
+
+        let mut authors: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for creator in self.mods.iter().filter_map(|m| m.creator.as_deref()) {
+            *authors.entry((personas.name_for(creator), creator)).or_default() += 1;
+        }
+        view.authors = authors
+            .into_iter()
+            .map(|((name, id), count)| (name.to_string(), id.to_string(), count))
+            .collect();
+
+        view.graph = DependencyGraph::build(&self.mods);
+        view.cycles = view.graph.cycles();
+        view.data_key = Some(data_key);
+    }
+}
+
+/// What the mods table and panels show about a game's mods, worked out from the mods,
+/// the game settings and the caches. Only rebuilt when one of its inputs changes.
+#[derive(Default)]
+pub struct ModsView {
+    rows_key: Option<(u64, Option<String>, bool)>, // (mods revision, author filter, ascending)
+    data_key: Option<(u64, GameConfig, PopularityConfig, u64)>, // (mods revision, settings, popularity, hour)
+    pub rows: Vec<usize>, // table rows as indexes into `GameEntry::mods`, filtered and sorted by ID
+    pub flags: Vec<ModFlags>, // same order as `GameEntry::mods`
+    pub counts: ModCounts,
+    pub game_version: Option<String>, // version label of the installed build
+    pub authors: Vec<(String, String, usize)>, // (name, steam_id, mod count), by name
+    pub graph: DependencyGraph,
+    pub cycles: Vec<Vec<String>>,
+}
+
+/// Problems found for one mod.
+#[derive(Default)]
+pub struct ModFlags {
+    pub missing_requirements: Vec<String>,
+    pub missing_dlc: Vec<u32>,
+    pub derived: Option<DerivedStatus>,
+    pub version_support: Option<VersionSupport>,
+    pub popularity_warning: Option<&'static str>,
+}
+
+/// Number of mods with each problem, for the warnings above the table.
+#[derive(Debug, Default, PartialEq)]
+pub struct ModCounts {
+    pub missing_requirements: usize,
+    pub missing_dlc: usize,
+    pub abandoned: usize,
+    pub not_updated_since_patch: usize,
+    pub unsupported: usize,
+    pub unpopular: usize,
+}
+
+impl ModCounts {
+    fn of(flags: &[ModFlags]) -> Self {
+        let count = |pred: fn(&ModFlags) -> bool| flags.iter().filter(|f| pred(f)).count();
+        Self {
+            missing_requirements: count(|f| !f.missing_requirements.is_empty()),
+            missing_dlc: count(|f| !f.missing_dlc.is_empty()),
+            abandoned: count(|f| matches!(f.derived, Some(DerivedStatus::PossiblyAbandoned { .. }))),
+            not_updated_since_patch: count(|f| matches!(f.derived, Some(DerivedStatus::NotUpdatedSincePatch { .. }))),
+            unsupported: count(|f| matches!(f.version_support, Some(VersionSupport::Unsupported(_)))),
+            unpopular: count(|f| f.popularity_warning.is_some()),
+        }
+    }
+}
+
+/// Something the user did in a view, or the result of a request made for one.
+/// Game actions apply to the selected game, results name the game they belong to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    SelectGame(usize),
+    SelectMod(Option<String>),
+    SetAuthorFilter(Option<String>),
+    ToggleSort,
+    SetSheetUrl(String),
+    LoadSheet(SheetSource),
+    SetHeaderRow(usize),
+    SetIdColumn(usize),
+    SetStatusColumn(usize),
+    SetNameColumn(usize),
+    Scan,
+    Fetch,
+    FetchAll,
+    SetCollectionInput(String),
+    LoadCollection(String),
+    CompareSubscriptions,
+    SetWatchInput(String),
+    Watch(Vec<String>),
+    MarkWatchedSeen(Option<String>), // None marks every watched item
+    Unwatch(String),
+    SetAbandonedDays(u32),
+    SetFlagOlderThanGame(bool),
+    SetVersionKeyInput(String),
+    AddVersion(String),
+    SetVersionLabel(String, String),
+    RemoveVersion(String),
+    SetHistorySince(String),
+    MarkNotificationRead(usize), // index into the notification history
+    ShowNotification(usize),     // mark it read and select its game
+    MarkAllNotificationsRead,
+    ClearNotifications,
+    SetBrokenStatuses(BTreeSet<String>),
+    /// (appid, collection members as (id, title)), None if the collection could not be loaded
+    CollectionLoaded(String, Option<Vec<(String, String)>>),
+    /// (appid, subscribed vs. installed), None if the subscriptions could not be loaded
+    SubscriptionsCompared(String, Option<SubscriptionDiff>),
+    LoadChangelog(String), // mod_id
+    /// (mod_id, remote time_updated when requested, change notes), None if the page could not be loaded
+    ChangelogLoaded(String, Option<String>, Option<Vec<ChangeNote>>),
+    Search(steam::WorkshopQuery),
+    /// (request_id, (total matches, items on the page)), None if the search failed
+    SearchLoaded(u64, Option<(u64, Vec<steam::ItemDetails>)>),
+    ApiKeyEdited,
+    TestApiKey,
+    ApiKeyChecked(bool), // true if the key was accepted
+}
+
+/// Data kept in the data directory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Store {
+    Personas,
+    Dlc,
+    Notifications,
+    History,
+    Watchlists,
+    BrokenStatuses,
+    Changelogs,
+}
+
+/// Work for the app to do after a state change; game indexes point into `AppState::games`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Effect {
+    LoadSheet(usize, SheetSource),
+    Scan(usize),
+    Fetch(usize),
+    FetchAll,
+    LoadCollection(usize, String),
+    CompareSubscriptions(usize),
+    LoadChangelog(String, Option<String>), // (mod_id, remote time_updated)
+    Search(u64, steam::WorkshopQuery),     // (request_id, query)
+    TestApiKey,
+    Save(Store),
+}
+
+pub struct AppState {
+    pub games: Option<Vec<GameEntry>>,
+    pub selected: Option<usize>,
+    pub sort_by_id_asc: bool,
+    pub personas: PersonaCache,
+    pub dlc: DlcCache,
+    pub notifications: Notifications,
+    pub history: History,
+    pub pending_changes: HashMap<String, PendingChanges>, // appid -> changes seen by the running fetch
+    pub last_session: HashMap<String, SessionItems>, // installed items when the app was last closed
+    pub broken_statuses: BrokenStatuses,
+    pub changelogs: ChangelogCache,
+    pub changelogs_loading: HashSet<String>, // mod IDs
+    pub changelogs_failed: HashSet<String>,
+    pub search: SearchState,
+    pub search_request: u64, // results of older requests are dropped
+    pub api_key_status: ApiKeyStatus,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            games: None,
+            selected: None,
+            sort_by_id_asc: true,
+            personas: PersonaCache::default(),
+            dlc: DlcCache::default(),
+            notifications: Notifications::default(),
+            history: History::default(),
+            pending_changes: HashMap::new(),
+            last_session: HashMap::new(),
+            broken_statuses: BrokenStatuses::default(),
+            changelogs: ChangelogCache::default(),
+            changelogs_loading: HashSet::new(),
+            changelogs_failed: HashSet::new(),
+            search: SearchState::Idle,
+            search_request: 0,
+            api_key_status: ApiKeyStatus::Unknown,
+        }
+    }
+}
+
+impl AppState {
+    /// State with the caches and history from the data directory.
+    pub fn load() -> Self {
+        Self {
+            personas: PersonaCache::load(),
+            dlc: DlcCache::load(),
+            notifications: Notifications::load(),
+            history: History::load(),
+            last_session: session::load_last_session(),
+            broken_statuses: BrokenStatuses::load(),
+            changelogs: ChangelogCache::load(),
+            ..Default::default()
+        }
+    }
+
+    pub fn selected_game(&self) -> Option<&GameEntry> {
+        self.games.as_ref()?.get(self.selected?)
+    }
+
+    fn selected_game_mut(&mut self) -> Option<&mut GameEntry> {
+        self.games.as_mut()?.get_mut(self.selected?)
+    }
+
+    /// Replace the game list after a reload and compare it with history and the last session.
+    pub fn set_games(&mut self, games: Option<Vec<GameEntry>>, now: u64) -> Vec<Effect> {
+        self.games = games;
+        self.selected = None;
+
+        let mut history_changed = false;
+        for game in self.games.iter_mut().flatten() {
+            history_changed |= self.history.record(game, &self.broken_statuses, now);
+            game.update_session_diff(&self.last_session);
+        }
+        if history_changed { vec![Effect::Save(Store::History)] } else { Vec::new() }
+    }
+
+    /// Any game changed while the app was closed.
+    pub fn has_session_changes(&self) -> bool {
+        self.games
+            .iter()
+            .flatten()
+            .any(|g| g.session_diff.as_ref().is_some_and(|d| !d.is_empty()))
+    }
+
+    pub fn update(&mut self, action: Action) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        match action {
+            Action::SelectGame(i) if self.games.as_ref().is_some_and(|g| i < g.len()) => {
+                // Search results belong to the game they were searched for
+                if self.selected != Some(i) {
+                    self.search = SearchState::Idle;
+                    self.search_request += 1;
+                }
+                self.selected = Some(i);
+            }
+            Action::ToggleSort => self.sort_by_id_asc = !self.sort_by_id_asc,
+            Action::MarkNotificationRead(n) => {
+                if let Some(item) = self.notifications.items.get_mut(n) {
+                    item.read = true;
+                    effects.push(Effect::Save(Store::Notifications));
+                }
+            }
+            Action::ShowNotification(n) => {
+                let Some(item) = self.notifications.items.get_mut(n) else {
+                    return effects;
+                };
+                item.read = true;
+                effects.push(Effect::Save(Store::Notifications));
+                let appid = item.appid.clone();
+                if let Some(i) = self.games.iter().flatten().position(|g| g.appid == appid) {
+                    effects.extend(self.update(Action::SelectGame(i)));
+                }
+                return effects;
+            }
+            Action::MarkAllNotificationsRead => {
+                self.notifications.mark_all_read();
+                effects.push(Effect::Save(Store::Notifications));
+            }
+            Action::ClearNotifications => {
+                self.notifications.items.clear();
+                effects.push(Effect::Save(Store::Notifications));
+            }
+            Action::SetBrokenStatuses(terms) => {
+                self.broken_statuses.terms = terms;
+                effects.push(Effect::Save(Store::BrokenStatuses));
+                return effects;
+            }
+            Action::CollectionLoaded(appid, members) => {
+                self.handle_collection(&appid, members);
+                return effects;
+            }
+            Action::SubscriptionsCompared(appid, diff) => {
+                self.handle_subscriptions(&appid, diff);
+                return effects;
+            }
+            Action::LoadChangelog(mod_id) => {
+                if self.changelogs_loading.insert(mod_id.clone()) {
+                    self.changelogs_failed.remove(&mod_id);
+                    let remote_updated = self
+                        .selected_game()
+                        .and_then(|g| g.mods.iter().find(|m| m.id == mod_id))
+                        .and_then(|m| m.last_updated.clone());
+                    effects.push(Effect::LoadChangelog(mod_id, remote_updated));
+                }
+                return effects;
+            }
+            Action::ChangelogLoaded(mod_id, remote_updated, notes) => {
+                self.changelogs_loading.remove(&mod_id);
+                match notes {
+                    Some(entries) => {
+                        self.changelogs.entries.insert(mod_id, CachedChangelog { remote_updated, entries });
+                        effects.push(Effect::Save(Store::Changelogs));
+                    }
+                    None => {
+                        self.changelogs_failed.insert(mod_id);
+                    }
+                }
+                return effects;
+            }
+            Action::Search(query) => {
+                self.search_request += 1;
+                self.search = SearchState::Loading;
+                effects.push(Effect::Search(self.search_request, query));
+                return effects;
+            }
+            Action::SearchLoaded(request_id, result) => {
+                // Only the latest search is shown
+                if request_id == self.search_request {
+                    self.search = match result {
+                        Some((total, items)) => SearchState::Loaded { total, items },
+                        None => SearchState::Failed,
+                    };
+                }
+                return effects;
+            }
+            Action::ApiKeyEdited => self.api_key_status = ApiKeyStatus::Unknown,
+            Action::TestApiKey => {
+                self.api_key_status = ApiKeyStatus::Checking;
+                effects.push(Effect::TestApiKey);
+            }
+            Action::ApiKeyChecked(valid) => {
+                self.api_key_status = if valid { ApiKeyStatus::Valid } else { ApiKeyStatus::Invalid };
+            }
+            Action::FetchAll => {
+                effects.push(Effect::FetchAll);
+                // Sheet statuses are needed for the dashboard too
+                for (i, game) in self.games.iter_mut().flatten().enumerate() {
+                    if matches!(game.sheet_status, SheetStatus::NotLoaded | SheetStatus::Failed(_))
+                        && let Some(source) = SheetSource::from_config(&game.spreadsheet)
+                    {
+                        effects.push(start_sheet_load(game, i, source));
+                    }
+                }
+            }
+            _ => {}
+        }
+        let Some(i) = self.selected else {
+            return effects;
+        };
+        let Some(game) = self.selected_game_mut() else {
+            return effects;
+        };
+
+        match action {
+            Action::SelectGame(_) => {
+                // Load the configured sheet once; a failed load is retried from the spreadsheet section
+                if matches!(game.sheet_status, SheetStatus::NotLoaded)
+                    && let Some(source) = SheetSource::from_config(&game.spreadsheet)
+                {
+                    effects.push(start_sheet_load(game, i, source));
+                }
+            }
+            Action::SelectMod(id) => game.selected_mod = id,
+            Action::SetAuthorFilter(id) => game.author_filter = id,
+            // Not bound to the selected game, handled above
+            Action::ToggleSort
+            | Action::FetchAll
+            | Action::MarkNotificationRead(_)
+            | Action::ShowNotification(_)
+            | Action::MarkAllNotificationsRead
+            | Action::ClearNotifications
+            | Action::SetBrokenStatuses(_)
+            | Action::CollectionLoaded(..)
+            | Action::SubscriptionsCompared(..)
+            | Action::LoadChangelog(_)
+            | Action::ChangelogLoaded(..)
+            | Action::Search(_)
+            | Action::SearchLoaded(..)
+            | Action::ApiKeyEdited
+            | Action::TestApiKey
+            | Action::ApiKeyChecked(_) => {}
+            Action::SetSheetUrl(url) => game.spreadsheet.sheet_url = url,
+            Action::LoadSheet(source) => {
+                match &source {
+                    SheetSource::Url(url) => game.spreadsheet.sheet_url = url.clone(),
+                    SheetSource::File(path) => game.spreadsheet.sheet_file = Some(path.clone()),
+                }
+                // Columns picked without a header row do not carry over to another sheet
+                if game.spreadsheet.header_row_index.is_none() {
+                    game.spreadsheet.id_col = None;
+                    game.spreadsheet.status_col = None;
+                    game.spreadsheet.name_col = None;
+                    game.reindex_sheet();
+                }
+                effects.push(start_sheet_load(game, i, source));
+            }
+            Action::SetHeaderRow(row) => {
+                game.spreadsheet.header_row_index = Some(row);
+                game.reindex_sheet();
+            }
+            Action::SetIdColumn(col) => {
+                game.spreadsheet.id_col = Some(col);
+                game.reindex_sheet();
+            }
+            Action::SetStatusColumn(col) => {
+                game.spreadsheet.status_col = Some(col);
+                game.reindex_sheet();
+            }
+            Action::SetNameColumn(col) => {
+                game.spreadsheet.name_col = Some(col);
+                game.reindex_sheet();
+            }
+            Action::Scan => effects.push(Effect::Scan(i)),
+            Action::Fetch => effects.push(Effect::Fetch(i)),
+            Action::SetCollectionInput(text) => game.collection_input = text,
+            Action::LoadCollection(id) => {
+                game.collection = CollectionState::Loading;
+                effects.push(Effect::LoadCollection(i, id));
+            }
+            Action::CompareSubscriptions => {
+                game.subscriptions = SubscriptionState::Loading;
+                effects.push(Effect::CompareSubscriptions(i));
+            }
+            Action::SetWatchInput(text) => game.watch_input = text,
+            Action::Watch(ids) => {
+                let before = game.watchlist.len();
+                for id in ids {
+                    if !game.watchlist.iter().any(|w| w.id == id) {
+                        game.watchlist.push(WatchedItem::new(id));
+                    }
+                }
+                if game.watchlist.len() != before {
+                    effects.push(Effect::Save(Store::Watchlists));
+                }
+            }
+            Action::MarkWatchedSeen(id) => {
+                for w in game.watchlist.iter_mut().filter(|w| id.as_ref().is_none_or(|id| &w.id == id)) {
+                    w.mark_seen();
+                }
+                effects.push(Effect::Save(Store::Watchlists));
+            }
+            Action::Unwatch(id) => {
+                game.watchlist.retain(|w| w.id != id);
+                effects.push(Effect::Save(Store::Watchlists));
+            }
+            Action::SetAbandonedDays(days) => game.settings.abandoned_days = days,
+            Action::SetFlagOlderThanGame(flag) => game.settings.flag_older_than_game = flag,
+            Action::SetVersionKeyInput(text) => game.version_key_input = text,
+            Action::AddVersion(key) => {
+                game.settings.versions.entry(key).or_default();
+            }
+            Action::SetVersionLabel(key, label) => {
+                game.settings.versions.insert(key, label);
+            }
+            Action::RemoveVersion(key) => {
+                game.settings.versions.remove(&key);
+            }
+            Action::SetHistorySince(text) => game.history_since = text,
+        }
+        effects
+    }
+
+    /// Scheduled check with freshly read app manifests, one per game: note game updates,
+    /// then rescan the mods and reload the sheets.
+    pub fn handle_scheduled_check(&mut self, manifests: Vec<Option<steam::AppManifest>>) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        for (i, (game, manifest)) in self.games.iter_mut().flatten().zip(manifests).enumerate() {
+            let old_build = game.manifest.as_ref().and_then(|m| m.buildid.clone());
+            let new_build = manifest.as_ref().and_then(|m| m.buildid.clone());
+            if let (Some(old), Some(new)) = (old_build, new_build)
+                && old != new
+            {
+                let text = format!("Game updated to build {}", new);
+                self.notifications.push(&game.appid, &game.name, text, Vec::new());
+                effects.push(Effect::Save(Store::Notifications));
+            }
+            game.manifest = manifest;
+            game.mods_revision += 1;
+
+            effects.push(Effect::Scan(i));
+            if let Some(source) = SheetSource::from_config(&game.spreadsheet) {
+                effects.push(start_sheet_load(game, i, source));
+            }
+        }
+        effects
+    }
+
+    /// Members of the collection loaded for `appid`, None if it could not be loaded.
+    fn handle_collection(&mut self, appid: &str, members: Option<Vec<(String, String)>>) {
+        if let Some(game) = self.games.iter_mut().flatten().find(|g| g.appid == appid) {
+            game.collection = match members {
+                Some(members) => CollectionState::Loaded(members),
+                None => CollectionState::Failed,
+            };
+        }
+    }
+
+    /// Subscriptions compared for `appid`, None if they could not be loaded.
+    fn handle_subscriptions(&mut self, appid: &str, diff: Option<SubscriptionDiff>) {
+        if let Some(game) = self.games.iter_mut().flatten().find(|g| g.appid == appid) {
+            game.subscriptions = match diff {
+                Some(diff) => SubscriptionState::Loaded(diff),
+                None => SubscriptionState::Failed,
+            };
+        }
+    }
+
+    /// Apply a message from a background job started for `appid` (or for every game).
+    pub fn handle_job(&mut self, kind: JobKind, appid: &str, message: JobMessage, now: u64) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        // Only the game the job was started for receives its results, or every game for a full check
+        let mut targets: Vec<&mut GameEntry> = self
+            .games
+            .iter_mut()
+            .flatten()
+            .filter(|g| kind == JobKind::FetchAll || g.appid == appid)
+            .collect();
+
+        match message {
+            JobMessage::Details(details) => {
+                for game in targets {
+                    for m in game.mods.iter_mut().filter(|m| m.id == details.id) {
+                        // Changes are only noticed against data fetched earlier in this session
+                        // (a mod that is already gone has no update time and stays unreported)
+                        let (was_updated, was_removed) = (m.last_updated.clone(), m.removed);
+                        m.apply_details(&details);
+                        let title = m.name.clone().unwrap_or_else(|| m.id.clone());
+                        if was_updated.is_none() {
+                            continue;
+                        }
+                        if m.removed && !was_removed {
+                            self.pending_changes.entry(game.appid.clone()).or_default().removed.push(title);
+                        } else if m.last_updated != was_updated {
+                            self.pending_changes.entry(game.appid.clone()).or_default().updated.push(title);
+                        }
+                    }
+                    // Authors may have changed, which the table filters on
+                    game.mods_revision += 1;
+                    for w in game.watchlist.iter_mut().filter(|w| w.id == details.id) {
+                        w.apply_details(&details);
+                    }
+                }
+            }
+            JobMessage::Requirements(requirements, titles) => {
+                for game in targets {
+                    for m in &mut game.mods {
+                        m.requires = requirements.get(&m.id).cloned();
+                    }
+                    game.dependency_titles.extend(titles.clone());
+                    game.mods_revision += 1;
+                }
+            }
+            JobMessage::Votes(votes) => {
+                for game in targets {
+                    for m in &mut game.mods {
+                        if let Some(vote) = votes.get(&m.id) {
+                            m.votes = Some(*vote);
+                        }
+                    }
+                    game.mods_revision += 1;
+                }
+            }
+            JobMessage::Personas(names) => {
+                self.personas.names.extend(names);
+                // Author names are shared by every game
+                self.games.iter_mut().flatten().for_each(|g| g.mods_revision += 1);
+                effects.push(Effect::Save(Store::Personas));
+            }
+            JobMessage::AppDependencies(dependencies, apps) => {
+                self.dlc.dependencies.extend(dependencies);
+                self.dlc.apps.extend(apps);
+                self.games.iter_mut().flatten().for_each(|g| g.mods_revision += 1);
+                effects.push(Effect::Save(Store::Dlc));
+            }
+            JobMessage::LocalDlc(games) => {
+                self.dlc.games.extend(games);
+                self.games.iter_mut().flatten().for_each(|g| g.mods_revision += 1);
+                effects.push(Effect::Save(Store::Dlc));
+            }
+            JobMessage::SheetCached(rows, saved_at) => {
+                if let Some(game) = targets.pop()
+                    && game.sheet_data.is_none()
+                {
+                    game.sheet_cached_at = Some(saved_at);
+                    game.set_sheet_data(rows);
+                }
+            }
+            JobMessage::SheetLoaded(rows) => {
+                if let Some(game) = targets.pop() {
+                    let before: HashMap<String, Option<String>> =
+                        game.mods.iter().map(|m| (m.id.clone(), m.status.clone())).collect();
+                    game.sheet_status = SheetStatus::Loaded;
+                    game.sheet_cached_at = None;
+                    game.set_sheet_data(rows);
+
+                    let changes: Vec<(String, String)> = game
+                        .mods
+                        .iter()
+                        .filter_map(|m| {
+                            let old = before.get(&m.id)?.as_ref()?;
+                            let new = m.status.as_ref().filter(|new| *new != old)?;
+                            Some((m.name.clone().unwrap_or_else(|| m.id.clone()), new.clone()))
+                        })
+                        .collect();
+                    if !changes.is_empty() {
+                        self.notifications.push_status_changes(&game.appid, &game.name, changes);
+                        effects.push(Effect::Save(Store::Notifications));
+                    }
+                    if self.history.record(game, &self.broken_statuses, now) {
+                        effects.push(Effect::Save(Store::History));
+                    }
+                }
+            }
+            JobMessage::Failed(error) if kind == JobKind::SheetLoad => {
+                if let Some(game) = targets.pop() {
+                    game.sheet_status = SheetStatus::Failed(error);
+                }
+            }
+            JobMessage::Scanned(local_mods) => {
+                if let Some(game) = targets.pop() {
+                    game.apply_scan(local_mods);
+                    game.apply_spreadsheet();
+                    game.update_session_diff(&self.last_session);
+                    if self.history.record(game, &self.broken_statuses, now) {
+                        effects.push(Effect::Save(Store::History));
+                    }
+                }
+            }
+            JobMessage::Done if matches!(kind, JobKind::Fetch | JobKind::FetchAll) => {
+                let mut history_changed = false;
+                for game in targets {
+                    history_changed |= self.history.record(game, &self.broken_statuses, now);
+                    // Remote times are known now
+                    game.update_session_diff(&self.last_session);
+                }
+                if history_changed {
+                    effects.push(Effect::Save(Store::History));
+                }
+                effects.push(Effect::Save(Store::Watchlists));
+                effects.extend(self.report_changes());
+            }
+            JobMessage::Failed(_) if matches!(kind, JobKind::Fetch | JobKind::FetchAll) => {
+                effects.extend(self.report_changes());
+            }
+            JobMessage::Progress(..) | JobMessage::Failed(_) | JobMessage::Done => {}
+        }
+        effects
+    }
+
+    /// Turn the changes seen by the finished fetch into notifications.
+    fn report_changes(&mut self) -> Option<Effect> {
+        if self.pending_changes.is_empty() {
+            return None;
+        }
+        for (appid, changes) in self.pending_changes.drain() {
+            let name = self
+                .games
+                .iter()
+                .flatten()
+                .find(|g| g.appid == appid)
+                .map_or_else(|| appid.clone(), |g| g.name.clone());
+            changes.report(&mut self.notifications, &appid, &name);
+        }
+        Some(Effect::Save(Store::Notifications))
+    }
+}
+
+/// Mark the sheet as loading and ask the app to start the job.
+fn start_sheet_load(game: &mut GameEntry, i: usize, source: SheetSource) -> Effect {
+    game.sheet_status = SheetStatus::Loading;
+    Effect::LoadSheet(i, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::ModState;
+
+    const APPID: &str = "294100";
+
+    fn state_with_mods(mods: &[(&str, u64)]) -> AppState {
+        let local_mods = mods.iter().map(|(id, time)| (id.to_string(), *time)).collect();
+        let game = GameEntry::new(APPID.to_string(), "RimWorld".to_string(), String::new(), local_mods);
+        let mut state = AppState::default();
+        state.set_games(Some(vec![game]), 1_000);
+        state
+    }
+
+    fn game(state: &AppState) -> &GameEntry {
+        &state.games.as_ref().unwrap()[0]
+    }
+
+    fn find<'a>(state: &'a AppState, id: &str) -> &'a WorkshopMod {
+        game(state).mods.iter().find(|m| m.id == id).unwrap()
+    }
+
+    fn state_of(state: &AppState, id: &str) -> ModState {
+        let m = find(state, id);
+        ModState::of(m, m.status.as_deref(), &state.broken_statuses)
+    }
+
+    fn details(id: &str, title: &str, time_updated: u64) -> steam::ItemDetails {
+        steam::ItemDetails {
+            id: id.to_string(),
+            title: title.to_string(),
+            time_updated: time_updated.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sheet(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn selecting_a_game_loads_its_configured_sheet_once() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.games.as_mut().unwrap()[0].spreadsheet.sheet_file = Some("mods.csv".to_string());
+
+        let effects = state.update(Action::SelectGame(0));
+        assert_eq!(state.selected, Some(0));
+        assert_eq!(effects, vec![Effect::LoadSheet(0, SheetSource::File("mods.csv".to_string()))]);
+
+        state.games.as_mut().unwrap()[0].sheet_status = SheetStatus::Loading;
+        assert!(state.update(Action::SelectGame(0)).is_empty());
+        assert!(state.update(Action::SelectGame(5)).is_empty());
+        assert_eq!(state.selected, Some(0));
+    }
+
+    #[test]
+    fn loaded_sheet_statuses_apply_once_columns_are_mapped() {
+        let mut state = state_with_mods(&[("1", 100), ("2", 100)]);
+        state.update(Action::SelectGame(0));
+
+        let source = SheetSource::Url("https://docs.google.com/spreadsheets/d/abc/edit".to_string());
+        assert_eq!(state.update(Action::LoadSheet(source.clone())), vec![Effect::LoadSheet(0, source)]);
+
+        let rows = sheet(&[&["ID", "Status", "Name"], &["1", "Broken", "First"], &["2", "OK", "Second"]]);
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetLoaded(rows), 1_000);
+        assert!(matches!(game(&state).sheet_status, SheetStatus::Loaded));
+        assert_eq!(find(&state, "1").status, None);
+
+        state.update(Action::SetHeaderRow(0));
+        state.update(Action::SetIdColumn(0));
+        state.update(Action::SetStatusColumn(1));
+        state.update(Action::SetNameColumn(2));
+        assert_eq!(find(&state, "1").status.as_deref(), Some("Broken"));
+        assert_eq!(find(&state, "1").name.as_deref(), Some("First"));
+        assert_eq!(state_of(&state, "1"), ModState::Broken);
+        assert_eq!(find(&state, "2").status.as_deref(), Some("OK"));
+    }
+
+    #[test]
+    fn cached_sheet_is_shown_until_the_download_arrives() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        let cfg = &mut state.games.as_mut().unwrap()[0].spreadsheet;
+        (cfg.header_row_index, cfg.id_col, cfg.status_col) = (Some(0), Some(0), Some(1));
+
+        let cached = sheet(&[&["ID", "Status"], &["1", "OK"]]);
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetCached(cached.clone(), 500), 1_000);
+        assert_eq!(game(&state).sheet_cached_at, Some(500));
+        assert_eq!(find(&state, "1").status.as_deref(), Some("OK"));
+
+        let rows = sheet(&[&["ID", "Status"], &["1", "Broken"]]);
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetLoaded(rows), 1_000);
+        assert_eq!(game(&state).sheet_cached_at, None);
+        assert_eq!(find(&state, "1").status.as_deref(), Some("Broken"));
+
+        // A later cached copy does not replace fresh data
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetCached(cached, 500), 1_000);
+        assert_eq!(find(&state, "1").status.as_deref(), Some("Broken"));
+    }
+
+    #[test]
+    fn loading_another_sheet_without_a_header_row_resets_the_columns() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+        state.update(Action::SetIdColumn(0));
+
+        state.update(Action::LoadSheet(SheetSource::File("other.csv".to_string())));
+        let cfg = &game(&state).spreadsheet;
+        assert_eq!(cfg.sheet_file.as_deref(), Some("other.csv"));
+        assert_eq!(cfg.id_col, None);
+    }
+
+    #[test]
+    fn failed_sheet_load_keeps_the_error() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::Failed("offline".to_string()), 1_000);
+        assert!(matches!(&game(&state).sheet_status, SheetStatus::Failed(e) if e == "offline"));
+    }
+
+    #[test]
+    fn checking_all_games_loads_the_sheets_not_loaded_yet() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.games.as_mut().unwrap()[0].spreadsheet.sheet_file = Some("mods.csv".to_string());
+
+        let effects = state.update(Action::FetchAll);
+        let load = Effect::LoadSheet(0, SheetSource::File("mods.csv".to_string()));
+        assert_eq!(effects, vec![Effect::FetchAll, load]);
+        assert!(matches!(game(&state).sheet_status, SheetStatus::Loading));
+        assert_eq!(state.update(Action::FetchAll), vec![Effect::FetchAll]);
+    }
+
+    #[test]
+    fn scheduled_check_notes_game_updates_and_reloads() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        let manifest = |build: &str| Some(steam::AppManifest { buildid: Some(build.to_string()), ..Default::default() });
+        state.games.as_mut().unwrap()[0].manifest = manifest("10");
+
+        assert_eq!(state.handle_scheduled_check(vec![manifest("10")]), vec![Effect::Scan(0)]);
+        assert!(state.notifications.items.is_empty());
+
+        let effects = state.handle_scheduled_check(vec![manifest("11")]);
+        assert_eq!(effects, vec![Effect::Save(Store::Notifications), Effect::Scan(0)]);
+        assert_eq!(state.notifications.items[0].text, "Game updated to build 11");
+        assert_eq!(game(&state).manifest.as_ref().unwrap().buildid.as_deref(), Some("11"));
+    }
+
+    #[test]
+    fn collection_and_subscription_results_reach_their_game() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+
+        state.update(Action::SetCollectionInput("123".to_string()));
+        let effects = state.update(Action::LoadCollection("123".to_string()));
+        assert_eq!(effects, vec![Effect::LoadCollection(0, "123".to_string())]);
+        assert!(matches!(game(&state).collection, CollectionState::Loading));
+        let members = vec![("1".to_string(), "First".to_string())];
+        state.update(Action::CollectionLoaded(APPID.to_string(), Some(members.clone())));
+        assert!(matches!(&game(&state).collection, CollectionState::Loaded(m) if *m == members));
+        assert_eq!(game(&state).collection_input, "123");
+
+        assert_eq!(state.update(Action::CompareSubscriptions), vec![Effect::CompareSubscriptions(0)]);
+        assert!(matches!(game(&state).subscriptions, SubscriptionState::Loading));
+        state.update(Action::SubscriptionsCompared("other".to_string(), Some(SubscriptionDiff::default())));
+        assert!(matches!(game(&state).subscriptions, SubscriptionState::Loading));
+        state.update(Action::SubscriptionsCompared(APPID.to_string(), None));
+        assert!(matches!(game(&state).subscriptions, SubscriptionState::Failed));
+    }
+
+    #[test]
+    fn change_notes_are_loaded_once_and_cached() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "First", 200)), 1_000);
+
+        let effects = state.update(Action::LoadChangelog("1".to_string()));
+        assert_eq!(effects, vec![Effect::LoadChangelog("1".to_string(), Some("200".to_string()))]);
+        assert!(state.update(Action::LoadChangelog("1".to_string())).is_empty());
+
+        state.update(Action::ChangelogLoaded("1".to_string(), Some("200".to_string()), None));
+        assert!(state.changelogs_failed.contains("1"));
+        state.update(Action::LoadChangelog("1".to_string()));
+        assert!(state.changelogs_failed.is_empty());
+
+        let notes = vec![ChangeNote { timestamp: 200, headline: "Fixes".to_string(), text: String::new() }];
+        let effects = state.update(Action::ChangelogLoaded("1".to_string(), Some("200".to_string()), Some(notes.clone())));
+        assert_eq!(effects, vec![Effect::Save(Store::Changelogs)]);
+        assert!(state.changelogs_loading.is_empty());
+        assert_eq!(state.changelogs.get("1", Some("200")), Some(notes.as_slice()));
+    }
+
+    #[test]
+    fn only_the_latest_search_of_the_selected_game_is_shown() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+        let query = steam::WorkshopQuery {
+            appid: APPID.to_string(),
+            text: "map".to_string(),
+            tags: Vec::new(),
+            sort: steam::SearchSort::MostPopular,
+            page: 1,
+            per_page: 30,
+        };
+
+        let first = state.search_request + 1;
+        assert_eq!(state.update(Action::Search(query.clone())), vec![Effect::Search(first, query.clone())]);
+        state.update(Action::Search(query));
+        assert!(matches!(state.search, SearchState::Loading));
+        state.update(Action::SearchLoaded(first, Some((1, vec![details("5", "Old", 100)]))));
+        assert!(matches!(state.search, SearchState::Loading));
+        state.update(Action::SearchLoaded(first + 1, Some((1, vec![details("6", "Map", 100)]))));
+        assert!(matches!(&state.search, SearchState::Loaded { items, .. } if items[0].id == "6"));
+
+        // Selecting the same game again keeps the results
+        state.update(Action::SelectGame(0));
+        assert!(matches!(state.search, SearchState::Loaded { .. }));
+        state.update(Action::SearchLoaded(first + 1, None));
+        assert!(matches!(state.search, SearchState::Failed));
+    }
+
+    #[test]
+    fn api_key_check_reports_its_outcome() {
+        let mut state = AppState::default();
+        assert_eq!(state.update(Action::TestApiKey), vec![Effect::TestApiKey]);
+        assert!(matches!(state.api_key_status, ApiKeyStatus::Checking));
+        state.update(Action::ApiKeyChecked(false));
+        assert!(matches!(state.api_key_status, ApiKeyStatus::Invalid));
+        state.update(Action::ApiKeyEdited);
+        assert!(matches!(state.api_key_status, ApiKeyStatus::Unknown));
+        state.update(Action::TestApiKey);
+        state.update(Action::ApiKeyChecked(true));
+        assert!(matches!(state.api_key_status, ApiKeyStatus::Valid));
+    }
+
+    #[test]
+    fn watchlist_edits_are_saved() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+        let save = vec![Effect::Save(Store::Watchlists)];
+
+        assert_eq!(state.update(Action::Watch(vec!["5".to_string(), "6".to_string()])), save);
+        assert!(state.update(Action::Watch(vec!["5".to_string()])).is_empty());
+
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("5", "Watched", 100)), 1_000);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("5", "Watched", 200)), 1_000);
+        assert!(game(&state).watchlist[0].has_changed());
+        assert_eq!(state.update(Action::MarkWatchedSeen(Some("5".to_string()))), save);
+        assert!(!game(&state).watchlist[0].has_changed());
+
+        assert_eq!(state.update(Action::Unwatch("5".to_string())), save);
+        let ids: Vec<&str> = game(&state).watchlist.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, ["6"]);
+    }
+
+    #[test]
+    fn notification_edits_are_saved() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.notifications.push("other", "Other", "Game updated to build 2".to_string(), Vec::new());
+        state.notifications.push(APPID, "RimWorld", "Game updated to build 11".to_string(), Vec::new());
+        let save = vec![Effect::Save(Store::Notifications)];
+
+        assert_eq!(state.update(Action::MarkNotificationRead(0)), save);
+        assert_eq!(state.notifications.unread(), 1);
+        assert!(state.update(Action::MarkNotificationRead(5)).is_empty());
+
+        assert_eq!(state.update(Action::ShowNotification(1)), save);
+        assert_eq!(state.selected, Some(0));
+        assert_eq!(state.notifications.unread(), 0);
+
+        state.notifications.push(APPID, "RimWorld", "1 was updated remotely".to_string(), Vec::new());
+        assert_eq!(state.update(Action::MarkAllNotificationsRead), save);
+        assert_eq!(state.notifications.unread(), 0);
+        assert_eq!(state.update(Action::ClearNotifications), save);
+        assert!(state.notifications.items.is_empty());
+    }
+
+    #[test]
+    fn game_settings_edits_apply_to_the_selected_game() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+
+        state.update(Action::SetAbandonedDays(30));
+        state.update(Action::SetFlagOlderThanGame(true));
+        state.update(Action::AddVersion("12345".to_string()));
+        state.update(Action::SetVersionLabel("12345".to_string(), "1.5".to_string()));
+        state.update(Action::AddVersion("12345".to_string()));
+        state.update(Action::AddVersion("beta".to_string()));
+        state.update(Action::RemoveVersion("beta".to_string()));
+        state.update(Action::SetHistorySince("2024-01-01".to_string()));
+
+        let game = game(&state);
+        assert_eq!(game.settings.abandoned_days, 30);
+        assert!(game.settings.flag_older_than_game);
+        assert_eq!(game.settings.versions.len(), 1);
+        assert_eq!(game.settings.versions["12345"], "1.5");
+        assert_eq!(game.history_since, "2024-01-01");
+    }
+
+    #[test]
+    fn fetched_details_decide_the_mod_state() {
+        let mut state = state_with_mods(&[("1", 100), ("2", 300)]);
+        state.update(Action::SelectGame(0));
+        assert_eq!(state.update(Action::Fetch), vec![Effect::Fetch(0)]);
+
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "Outdated mod", 200)), 1_000);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("2", "Current mod", 200)), 1_000);
+        let removed = steam::ItemDetails { id: "3".to_string(), removed: true, ..Default::default() };
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(removed), 1_000);
+        let effects = state.handle_job(JobKind::Fetch, APPID, JobMessage::Done, 1_000);
+
+        assert_eq!(state_of(&state, "1"), ModState::Outdated);
+        assert_eq!(state_of(&state, "2"), ModState::UpToDate);
+        assert_eq!(find(&state, "1").name.as_deref(), Some("Outdated mod"));
+        assert!(effects.contains(&Effect::Save(Store::History)));
+        assert_eq!(state.history.timeline(APPID, "1").last().unwrap().state, ModState::Outdated);
+    }
+
+    #[test]
+    fn results_of_another_game_are_ignored_but_a_full_check_reaches_all() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.handle_job(JobKind::Fetch, "999", JobMessage::Details(details("1", "Mod", 200)), 1_000);
+        assert_eq!(find(&state, "1").name, None);
+
+        state.handle_job(JobKind::FetchAll, "", JobMessage::Details(details("1", "Mod", 200)), 1_000);
+        assert_eq!(find(&state, "1").name.as_deref(), Some("Mod"));
+    }
+
+    #[test]
+    fn remote_updates_and_removals_become_notifications() {
+        let mut state = state_with_mods(&[("1", 100), ("2", 100), ("3", 100)]);
+        let gone = steam::ItemDetails { id: "3".to_string(), removed: true, ..Default::default() };
+        for message in [
+            JobMessage::Details(details("1", "First", 100)),
+            JobMessage::Details(details("2", "Second", 100)),
+            JobMessage::Details(gone.clone()),
+            JobMessage::Done,
+        ] {
+            state.handle_job(JobKind::Fetch, APPID, message, 1_000);
+        }
+        // The first fetch is only the baseline, also for mods removed before it
+        assert!(state.notifications.items.is_empty());
+        assert!(find(&state, "3").removed);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(gone), 2_000);
+
+        let removed = steam::ItemDetails { id: "2".to_string(), removed: true, ..Default::default() };
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "First", 500)), 2_000);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(removed), 2_000);
+        let effects = state.handle_job(JobKind::Fetch, APPID, JobMessage::Done, 2_000);
+
+        let texts: Vec<&str> = state.notifications.items.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, ["First was updated remotely", "Second was removed from the Workshop"]);
+        assert!(effects.contains(&Effect::Save(Store::Notifications)));
+        assert_eq!(state.notifications.unread_for(APPID), 2);
+    }
+
+    #[test]
+    fn sheet_status_changes_become_notifications() {
+        let mut state = state_with_mods(&[("1", 100)]);
+        state.update(Action::SelectGame(0));
+        state.update(Action::SetHeaderRow(0));
+        state.update(Action::SetIdColumn(0));
+        state.update(Action::SetStatusColumn(1));
+
+        let load = |state: &mut AppState, status: &str| {
+            let rows = sheet(&[&["ID", "Status"], &["1", status]]);
+            state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetLoaded(rows), 1_000)
+        };
+        load(&mut state, "OK");
+        assert!(state.notifications.items.is_empty());
+        let effects = load(&mut state, "Broken");
+        assert_eq!(state.notifications.items[0].text, "Sheet status for 1 changed to Broken");
+        assert!(effects.contains(&Effect::Save(Store::Notifications)));
+    }
+
+    #[test]
+    fn rescan_keeps_fetched_data_of_installed_mods() {
+        let mut state = state_with_mods(&[("1", 100), ("2", 100)]);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "First", 200)), 1_000);
+
+        let local_mods = HashMap::from([("1".to_string(), 250), ("3".to_string(), 100)]);
+        state.handle_job(JobKind::Scan, APPID, JobMessage::Scanned(local_mods), 1_000);
+
+        let mut ids: Vec<&str> = game(&state).mods.iter().map(|m| m.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, ["1", "3"]);
+        assert_eq!(find(&state, "1").name.as_deref(), Some("First"));
+        assert_eq!(state_of(&state, "1"), ModState::UpToDate);
+    }
+
+    fn refresh_view(state: &mut AppState, now: u64) -> &GameEntry {
+        let ascending = state.sort_by_id_asc;
+        let game = &mut state.games.as_mut().unwrap()[0];
+        game.refresh_mods_view(ascending, &state.personas, &state.dlc, &PopularityConfig::default(), now);
+        game
+    }
+
+    fn view_ids(state: &mut AppState) -> Vec<String> {
+        let game = refresh_view(state, 1_000);
+        game.mods_view.rows.iter().map(|&i| game.mods[i].id.clone()).collect()
+    }
+
+    #[test]
+    fn mods_view_follows_sort_filter_and_scans() {
+        let mut state = state_with_mods(&[("2", 100), ("3", 100), ("1", 100)]);
+        state.update(Action::SelectGame(0));
+        assert_eq!(view_ids(&mut state), ["1", "2", "3"]);
+
+        state.update(Action::ToggleSort);
+        assert_eq!(view_ids(&mut state), ["3", "2", "1"]);
+
+        let mut by_author = details("2", "Second", 200);
+        by_author.creator = Some("76561198000000000".to_string());
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(by_author), 1_000);
+        state.update(Action::SetAuthorFilter(Some("76561198000000000".to_string())));
+        assert_eq!(view_ids(&mut state), ["2"]);
+
+        state.update(Action::SetAuthorFilter(None));
+        let local_mods = HashMap::from([("1".to_string(), 100), ("4".to_string(), 100)]);
+        state.handle_job(JobKind::Scan, APPID, JobMessage::Scanned(local_mods), 1_000);
+        assert_eq!(view_ids(&mut state), ["4", "1"]);
+    }
+
+    #[test]
+    fn mods_view_derived_data_follows_mods_and_settings() {
+        const DAY: u64 = 24 * 60 * 60;
+        let mut state = state_with_mods(&[("1", 100), ("2", 100)]);
+        state.update(Action::SelectGame(0));
+        let requirements = HashMap::from([
+            ("1".to_string(), vec!["9".to_string()]),
+            ("2".to_string(), vec!["1".to_string()]),
+        ]);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Requirements(requirements, HashMap::new()), 1_000);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "First", 100)), 1_000);
+
+        let view = &refresh_view(&mut state, 400 * DAY).mods_view;
+        assert_eq!(view.counts, ModCounts { missing_requirements: 1, abandoned: 1, ..Default::default() });
+        assert_eq!(view.graph.requires("2"), ["1"]);
+        assert!(view.cycles.is_empty());
+
+        // New requirements invalidate the cached results
+        let requirements = HashMap::from([("1".to_string(), Vec::new()), ("2".to_string(), vec!["1".to_string()])]);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Requirements(requirements, HashMap::new()), 1_000);
+        let view = &refresh_view(&mut state, 400 * DAY).mods_view;
+        assert_eq!(view.counts, ModCounts { abandoned: 1, ..Default::default() });
+        assert!(view.graph.requires("1").is_empty());
+
+        state.update(Action::SetAbandonedDays(0));
+        let view = &refresh_view(&mut state, 400 * DAY).mods_view;
+        assert_eq!(view.counts, ModCounts::default());
+    }
+
+    #[test]
+    fn health_is_recounted_when_mods_or_broken_statuses_change() {
+        let mut state = state_with_mods(&[("1", 100), ("2", 100)]);
+        let cfg = &mut state.games.as_mut().unwrap()[0].spreadsheet;
+        (cfg.header_row_index, cfg.id_col, cfg.status_col) = (Some(0), Some(0), Some(1));
+        let rows = sheet(&[&["ID", "Status"], &["1", "Broken"], &["2", "Flaky"]]);
+        state.handle_job(JobKind::SheetLoad, APPID, JobMessage::SheetLoaded(rows), 1_000);
+
+        let refresh = |state: &mut AppState| {
+            let game = &mut state.games.as_mut().unwrap()[0];
+            game.refresh_health(&state.broken_statuses);
+            (game.health.total, game.health.broken)
+        };
+        assert_eq!(refresh(&mut state), (2, 1));
+
+        let mut terms = state.broken_statuses.terms.clone();
+        terms.insert("flaky".to_string());
+        assert_eq!(state.update(Action::SetBrokenStatuses(terms)), vec![Effect::Save(Store::BrokenStatuses)]);
+        assert_eq!(refresh(&mut state), (2, 2));
+    }
+
+    #[test]
+    fn session_diff_lists_changes_since_the_last_session() {
+        let mut state = AppState::default();
+        let previous = HashMap::from([
+            ("1".to_string(), session::InstalledItem { local_updated: 100, remote_updated: Some(100), title: None, saved_at: None }),
+            ("2".to_string(), session::InstalledItem { local_updated: 100, remote_updated: None, title: Some("Gone".to_string()), saved_at: None }),
+        ]);
+        state.last_session.insert(APPID.to_string(), previous);
+
+        let local_mods = HashMap::from([("1".to_string(), 100), ("3".to_string(), 100)]);
+        let entry = GameEntry::new(APPID.to_string(), "RimWorld".to_string(), String::new(), local_mods);
+        state.set_games(Some(vec![entry]), 1_000);
+        assert!(state.has_session_changes());
+
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Details(details("1", "First", 400)), 1_000);
+        state.handle_job(JobKind::Fetch, APPID, JobMessage::Done, 1_000);
+        let diff = game(&state).session_diff.as_ref().unwrap();
+        assert_eq!(diff.installed, ["3"]);
+        assert_eq!(diff.removed, [("2".to_string(), "Gone".to_string())]);
+        assert_eq!(diff.not_downloaded, ["1"]);
+        assert!(diff.updated.is_empty());
+    }
+}
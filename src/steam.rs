@@ -1,11 +1,124 @@
-use std::{collections::HashMap, fs};
+use std::{collections::{HashMap, HashSet}, fs, sync::Arc};
 use std::path::PathBuf;
 use serde_json::Value;
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::{sync::Semaphore, task::JoinSet, time::sleep};
 use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use crate::appinfo;
 use crate::parser::parse_vdf;
 
+/// Remote details of a Workshop item as returned by `GetPublishedFileDetails`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemDetails {
+    pub id: String,
+    pub title: String,
+    pub time_updated: String,
+    pub preview_url: Option<String>,
+    pub creator: Option<String>, // SteamID64
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub subscriptions: Option<u64>,
+    #[serde(default)]
+    pub favorited: Option<u64>,
+    /// Only returned by the authenticated `IPublishedFileService` endpoints
+    #[serde(default)]
+    pub votes: Option<VoteData>,
+    /// Deleted, hidden or banned: the API answers without details
+    #[serde(default)]
+    pub removed: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VoteData {
+    pub score: f32, // 0.0 - 1.0
+    pub votes_up: u64,
+    pub votes_down: u64,
+}
+
+impl VoteData {
+    fn from_json(vote_data: &Value) -> Option<Self> {
+        Some(Self {
+            score: vote_data["score"].as_f64()? as f32,
+            votes_up: vote_data["votes_up"].as_u64().unwrap_or(0),
+            votes_down: vote_data["votes_down"].as_u64().unwrap_or(0),
+        })
+    }
+}
+
+impl ItemDetails {
+    /// Read an item from a `publishedfiledetails` entry; the same shape is returned
+    /// by `GetPublishedFileDetails` and `IPublishedFileService`.
+    fn from_json(item: &Value) -> Self {
+        let non_empty = |v: &Value| v.as_str().filter(|s| !s.is_empty()).map(str::to_string);
+        Self {
+            id: item["publishedfileid"].as_str().unwrap_or("").to_string(),
+            title: item["title"].as_str().unwrap_or("").to_string(),
+            time_updated: item["time_updated"]
+                .as_i64()
+                .map(|ts| ts.to_string())
+                .unwrap_or_default(),
+            preview_url: non_empty(&item["preview_url"]),
+            creator: non_empty(&item["creator"]),
+            tags: item["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|t| t["tag"].as_str().map(str::to_string))
+                .collect(),
+            subscriptions: as_count(&item["subscriptions"]),
+            favorited: as_count(&item["favorited"]),
+            votes: VoteData::from_json(&item["vote_data"]),
+            // result 1 is OK; 9 (file not found) and friends come without details
+            removed: item["result"].as_i64().is_some_and(|r| r != 1),
+        }
+    }
+}
+
+/// Counters come as numbers from some endpoints and as strings from others.
+fn as_count(value: &Value) -> Option<u64> {
+    value.as_u64().or_else(|| value.as_str()?.parse().ok())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchSort {
+    MostPopular,
+    MostRecent,
+    Trending,
+}
+
+impl SearchSort {
+    pub const ALL: [SearchSort; 3] = [SearchSort::MostPopular, SearchSort::MostRecent, SearchSort::Trending];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchSort::MostPopular => "Most popular",
+            SearchSort::MostRecent => "Most recent",
+            SearchSort::Trending => "Trending",
+        }
+    }
+
+    /// `EPublishedFileQueryType` value used by `QueryFiles`
+    fn query_type(self) -> u32 {
+        match self {
+            SearchSort::MostPopular => 0,
+            SearchSort::MostRecent => 1,
+            SearchSort::Trending => 3,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkshopQuery {
+    pub appid: String,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub sort: SearchSort,
+    pub page: u32, // 1-based
+    pub per_page: u32,
+}
+
 pub fn default_steam_path() -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     {
@@ -24,36 +137,34 @@ pub fn default_steam_path() -> Option<PathBuf> {
 pub fn get_installed_games(steam_path: &str) -> Option<Vec<(String, String, String)>> {
     let path = PathBuf::from(steam_path).join("steamapps").join("libraryfolders.vdf");
     let text = fs::read_to_string(&path).ok()?;
-    println!("✅ Loaded libraryfolders.vdf from: {}", path.display());
 
     let parsed = parse_vdf(&text);
     let mut games = Vec::new();
 
     if let Some(folders) = parsed.get("libraryfolders").and_then(|v| v.as_object()) {
         for (_id, folder) in folders {
-            if let Some(lib_path) = folder.get("path").and_then(|v| v.as_str()) {
-                if let Some(apps) = folder.get("apps").and_then(|v| v.as_object()) {
-                    for (appid, _) in apps {
-                        // Look for appmanifest_<appid>.acf
-                        let manifest = PathBuf::from(lib_path)
-                            .join("steamapps")
-                            .join(format!("appmanifest_{}.acf", appid));
-
-                        let mut game_name = String::from("<unknown>");
-                        if let Ok(text) = fs::read_to_string(&manifest) {
-                            let manifest_parsed = parse_vdf(&text);
-                            if let Some(name) = manifest_parsed
-                                .get("AppState")
-                                .and_then(|v| v.get("name"))
-                                .and_then(|v| v.as_str())
-                            {
-                                game_name = name.to_string();
-                            }
-                        }
+            if let Some(lib_path) = folder.get("path").and_then(|v| v.as_str())
+                && let Some(apps) = folder.get("apps").and_then(|v| v.as_object())
+            {
+                for (appid, _) in apps {
+                    // Look for appmanifest_<appid>.acf
+                    let manifest = PathBuf::from(lib_path)
+                        .join("steamapps")
+                        .join(format!("appmanifest_{}.acf", appid));
 
-                        println!("🎮 AppID {} -> {} (in {})", appid, game_name, lib_path);
-                        games.push((appid.clone(), game_name, lib_path.to_string()));
+                    let mut game_name = String::from("<unknown>");
+                    if let Ok(text) = fs::read_to_string(&manifest) {
+                        let manifest_parsed = parse_vdf(&text);
+                        if let Some(name) = manifest_parsed
+                            .get("AppState")
+                            .and_then(|v| v.get("name"))
+                            .and_then(|v| v.as_str())
+                        {
+                            game_name = name.to_string();
+                        }
                     }
+
+                    games.push((appid.clone(), game_name, lib_path.to_string()));
                 }
             }
         }
@@ -62,6 +173,101 @@ pub fn get_installed_games(steam_path: &str) -> Option<Vec<(String, String, Stri
     Some(games)
 }
 
+/// SteamID64 of the account that logged in most recently, from `config/loginusers.vdf`.
+pub fn most_recent_steam_id(steam_path: &str) -> Option<String> {
+    let path = PathBuf::from(steam_path).join("config").join("loginusers.vdf");
+    let parsed = parse_vdf(&fs::read_to_string(path).ok()?);
+    let users = parsed.get("users")?.as_object()?;
+
+    users
+        .iter()
+        .find(|(_, user)| user.get("MostRecent").and_then(|v| v.as_str()) == Some("1"))
+        .or_else(|| users.iter().next())
+        .map(|(id, _)| id.clone())
+}
+
+/// Store information about an app that Workshop items depend on.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppInfo {
+    pub name: String,
+    pub dlc_for: Option<u32>, // base game app ID when the app is a DLC
+}
+
+/// A game's DLC as listed in the local app info cache.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LocalDlc {
+    pub dlc: HashSet<u32>,
+    /// DLC that ship depots of their own; owning one makes Steam install its depot,
+    /// while DLC without depots only unlock content already in the game files
+    pub with_depots: HashSet<u32>,
+}
+
+/// DLC of the given games from `appcache/appinfo.vdf`. Games not in the cache are left out.
+pub fn read_local_dlc(steam_path: &str, game_appids: &[u32]) -> HashMap<u32, LocalDlc> {
+    let wanted: HashSet<u32> = game_appids.iter().copied().collect();
+    appinfo::read_appinfo(steam_path, &wanted)
+        .into_iter()
+        .map(|(appid, info)| {
+            let with_depots: HashSet<u32> = info["depots"]
+                .as_object()
+                .into_iter()
+                .flat_map(|depots| depots.values())
+                .filter_map(|depot| depot.get("dlcappid")?.as_str()?.parse().ok())
+                .collect();
+            let mut dlc: HashSet<u32> = info["extended"]["listofdlc"]
+                .as_str()
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|id| id.trim().parse().ok())
+                .collect();
+            dlc.extend(&with_depots);
+            (appid, LocalDlc { dlc, with_depots })
+        })
+        .collect()
+}
+
+/// Build information from `appmanifest_<appid>.acf`.
+#[derive(Clone, Debug, Default)]
+pub struct AppManifest {
+    pub buildid: Option<String>,
+    pub last_updated: Option<u64>, // when Steam last updated the game locally
+    pub beta_key: Option<String>,  // selected beta branch, None on the default branch
+    pub installed_dlc: HashSet<u32>, // DLC app IDs with installed depots
+}
+
+pub fn read_app_manifest(lib_root: &str, appid: &str) -> Option<AppManifest> {
+    let path = PathBuf::from(lib_root)
+        .join("steamapps")
+        .join(format!("appmanifest_{}.acf", appid));
+    let parsed = parse_vdf(&fs::read_to_string(path).ok()?);
+    let state = parsed.get("AppState")?;
+    let field = |key: &str| state.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    // Older manifests write "betakey" in lower case
+    let beta_key = state
+        .get("UserConfig")
+        .and_then(|c| c.get("BetaKey").or_else(|| c.get("betakey")))
+        .and_then(|v| v.as_str())
+        .filter(|key| !key.is_empty())
+        .map(str::to_string);
+
+    // DLC depots carry the DLC's app ID
+    let installed_dlc = state
+        .get("InstalledDepots")
+        .and_then(|d| d.as_object())
+        .into_iter()
+        .flat_map(|depots| depots.values())
+        .filter_map(|depot| depot.get("dlcappid")?.as_str()?.parse().ok())
+        .collect();
+
+    Some(AppManifest {
+        buildid: field("buildid"),
+        last_updated: field("LastUpdated").and_then(|v| v.parse().ok()),
+        beta_key,
+        installed_dlc,
+    })
+}
+
 pub fn get_active_mods(lib_root: &str, appid: &str) -> HashMap<String, u64> {
     let manifest_path = format!("{}/steamapps/workshop/appworkshop_{}.acf", lib_root, appid);
     let data = match std::fs::read_to_string(&manifest_path) {
@@ -78,55 +284,509 @@ pub fn get_active_mods(lib_root: &str, appid: &str) -> HashMap<String, u64> {
         .and_then(|v| v.as_object())
     {
         for (mod_id, entry) in items {
-            if let Some(obj) = entry.as_object() {
-                if let Some(time) = obj.get("timeupdated").and_then(|v| v.as_str()) {
-                    if let Ok(ts) = time.parse::<u64>() {
-                        mods.insert(mod_id.clone(), ts);
-                    }
-                }
+            if let Some(obj) = entry.as_object()
+                && let Some(time) = obj.get("timeupdated").and_then(|v| v.as_str())
+                && let Ok(ts) = time.parse::<u64>()
+            {
+                mods.insert(mod_id.clone(), ts);
             }
         }
     }
     mods
 }
 
-pub async fn fetch_mods_details(mod_ids: &[String]) -> Vec<(String, String, String)> {
-    let client = Client::new();
-    let mut results = Vec::new();
+/// Link to an item's Workshop page.
+pub fn workshop_url(id: &str) -> String {
+    format!("https://steamcommunity.com/sharedfiles/filedetails/?id={}", id)
+}
+
+/// Extract a Workshop item ID from a URL (`...filedetails/?id=123`) or a bare ID.
+pub fn parse_workshop_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let candidate = match input.split_once('?') {
+        // Match the `id` key exactly, `appid=` must not count
+        Some((_, query)) => query.split(['&', '#']).find_map(|pair| pair.strip_prefix("id="))?,
+        None => input,
+    };
+    if !candidate.is_empty() && candidate.chars().all(|c| c.is_ascii_digit()) {
+        Some(candidate.to_string())
+    } else {
+        None
+    }
+}
+
+/// Item ID from a Steam Community link; unlike `parse_workshop_id`, bare IDs are rejected.
+pub fn parse_workshop_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let host = url.split_once("://").map_or(url, |(_, rest)| rest);
+    if !host.starts_with("steamcommunity.com/") || !url.contains('?') {
+        return None;
+    }
+    parse_workshop_id(url)
+}
+
+/// Client for the Steam Web API. Keyless endpoints always work; the authenticated
+/// ones need a Web API key and return nothing without it.
+#[derive(Clone)]
+pub struct WorkshopClient {
+    http: Client,
+    api_key: Option<String>,
+    concurrency: usize,
+}
+
+impl WorkshopClient {
+    /// `concurrency` is the number of request batches allowed in flight at once.
+    pub fn new(api_key: &str, concurrency: usize) -> Self {
+        Self {
+            http: Client::new(),
+            api_key: Some(api_key.trim().to_string()).filter(|k| !k.is_empty()),
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    pub fn has_api_key(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    /// Fetch details in batches of 50, running up to `concurrency` batches at once.
+    /// `on_batch` is called with every finished batch so callers can report progress.
+    pub async fn fetch_mods_details<F>(&self, mod_ids: &[String], mut on_batch: F) -> Vec<ItemDetails>
+    where
+        F: FnMut(&[ItemDetails]),
+    {
+        let mut results = Vec::new();
+        self.run_batched(
+            mod_ids,
+            |client, chunk| async move { fetch_batch(&client, &chunk).await },
+            |batch: Vec<_>| {
+                on_batch(&batch);
+                results.extend(batch);
+            },
+        )
+        .await;
+        results
+    }
+
+    /// Fetch the required items ("children") of each mod via `GetCollectionDetails`.
+    /// Returns (mod_id -> required item IDs); mods without requirements map to an empty list.
+    pub async fn fetch_mods_requirements(&self, mod_ids: &[String]) -> HashMap<String, Vec<String>> {
+        let mut requirements = HashMap::new();
+        self.run_batched(
+            mod_ids,
+            |client, chunk| async move { fetch_collection_children(&client, &chunk).await },
+            |batch| {
+                for (id, children) in batch.into_iter().flatten() {
+                    requirements.insert(id, children.into_iter().map(|(child, _)| child).collect());
+                }
+            },
+        )
+        .await;
+        requirements
+    }
+
+    /// Resolve a collection into the item IDs it contains, following nested collections.
+    /// Returns None if the collection itself could not be fetched.
+    pub async fn fetch_collection_items(&self, collection_id: &str) -> Option<Vec<String>> {
+        let mut items = Vec::new();
+        let mut seen = HashSet::from([collection_id.to_string()]);
+        let mut pending = vec![collection_id.to_string()];
+        let mut first = true;
+
+        while !pending.is_empty() {
+            let batch: Vec<String> = std::mem::take(&mut pending);
+            let children = fetch_collection_children(&self.http, &batch).await;
+            if first && children.is_none() {
+                return None;
+            }
+            first = false;
+
+            for (child_id, filetype) in children.into_iter().flatten().flat_map(|(_, c)| c) {
+                if !seen.insert(child_id.clone()) {
+                    continue;
+                }
+                // filetype 2 marks a nested collection
+                if filetype == 2 {
+                    pending.push(child_id);
+                } else {
+                    items.push(child_id);
+                }
+            }
+        }
+
+        Some(items)
+    }
 
-    // Process in chunks of 50
-    for chunk in mod_ids.chunks(50) {
-        let mut params: Vec<(String, String)> = Vec::new();
-        params.push(("itemcount".to_string(), chunk.len().to_string()));
+    /// Resolve SteamID64s to persona names via `ISteamUser/GetPlayerSummaries` (100 IDs per request).
+    pub async fn fetch_persona_names(&self, steam_ids: &[String]) -> HashMap<String, String> {
+        let mut names = HashMap::new();
+        let Some(api_key) = &self.api_key else {
+            return names;
+        };
 
-        for (i, id) in chunk.iter().enumerate() {
-            params.push((format!("publishedfileids[{}]", i), id.clone()));
+        for chunk in steam_ids.chunks(100) {
+            let Ok(resp) = self
+                .http
+                .get("https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/")
+                .query(&[("key", api_key.as_str()), ("steamids", &chunk.join(","))])
+                .send()
+                .await
+            else {
+                continue;
+            };
+            if let Ok(json) = resp.json::<Value>().await {
+                for player in json["response"]["players"].as_array().into_iter().flatten() {
+                    if let (Some(id), Some(name)) = (player["steamid"].as_str(), player["personaname"].as_str()) {
+                        names.insert(id.to_string(), name.to_string());
+                    }
+                }
+            }
         }
 
-        if let Ok(resp) = client
-            .post("https://api.steampowered.com/ISteamRemoteStorage/GetPublishedFileDetails/v1/")
-            .form(&params) // reqwest accepts Vec<(String, String)>
+        names
+    }
+
+    /// Vote data per item via `IPublishedFileService/GetDetails`; empty without a key.
+    pub async fn fetch_vote_data(&self, mod_ids: &[String]) -> HashMap<String, VoteData> {
+        let mut votes = HashMap::new();
+        let Some(api_key) = self.api_key.clone() else {
+            return votes;
+        };
+        self.run_batched(
+            mod_ids,
+            move |client, chunk| {
+                let api_key = api_key.clone();
+                async move {
+                    let mut params = vec![
+                        ("key".to_string(), api_key),
+                        ("includevotes".to_string(), "true".to_string()),
+                    ];
+                    for (i, id) in chunk.iter().enumerate() {
+                        params.push((format!("publishedfileids[{}]", i), id.clone()));
+                    }
+                    let resp = client
+                        .get("https://api.steampowered.com/IPublishedFileService/GetDetails/v1/")
+                        .query(&params)
+                        .send()
+                        .await
+                        .ok()?;
+                    resp.json::<Value>().await.ok()
+                }
+            },
+            |json| {
+                let items = json.as_ref().and_then(|j| j["response"]["publishedfiledetails"].as_array());
+                for item in items.into_iter().flatten() {
+                    if let (Some(id), Some(vote)) =
+                        (item["publishedfileid"].as_str(), VoteData::from_json(&item["vote_data"]))
+                    {
+                        votes.insert(id.to_string(), vote);
+                    }
+                }
+            },
+        )
+        .await;
+        votes
+    }
+
+    /// Search the Workshop of an app via `IPublishedFileService/QueryFiles`.
+    /// Returns (total matches, items on the requested page), None without a key or on failure.
+    pub async fn query_files(&self, query: &WorkshopQuery) -> Option<(u64, Vec<ItemDetails>)> {
+        let api_key = self.api_key.as_ref()?;
+        let mut params: Vec<(String, String)> = vec![
+            ("key".into(), api_key.clone()),
+            ("appid".into(), query.appid.clone()),
+            ("query_type".into(), query.sort.query_type().to_string()),
+            ("page".into(), query.page.max(1).to_string()),
+            ("numperpage".into(), query.per_page.to_string()),
+            ("search_text".into(), query.text.clone()),
+            ("return_details".into(), "true".into()),
+            ("return_tags".into(), "true".into()),
+            ("return_previews".into(), "true".into()),
+            ("return_vote_data".into(), "true".into()),
+            ("match_all_tags".into(), "true".into()),
+        ];
+        if query.sort == SearchSort::Trending {
+            params.push(("days".into(), "7".into()));
+        }
+        for (i, tag) in query.tags.iter().enumerate() {
+            params.push((format!("requiredtags[{}]", i), tag.clone()));
+        }
+
+        let resp = self
+            .http
+            .get("https://api.steampowered.com/IPublishedFileService/QueryFiles/v1/")
+            .query(&params)
             .send()
             .await
-        {
-            if let Ok(json) = resp.json::<Value>().await {
-                if let Some(items) = json["response"]["publishedfiledetails"].as_array() {
-                    for item in items {
-                        let id = item["publishedfileid"].as_str().unwrap_or("").to_string();
-                        let title = item["title"].as_str().unwrap_or("").to_string();
-                        let updated = item["time_updated"]
-                            .as_i64()
-                            .map(|ts| ts.to_string())
-                            .unwrap_or_default();
-                        results.push((id, title, updated));
+            .ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let json = resp.json::<Value>().await.ok()?;
+        let response = &json["response"];
+        let total = response["total"].as_u64().unwrap_or(0);
+        let items = response["publishedfiledetails"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(ItemDetails::from_json)
+            .collect();
+        Some((total, items))
+    }
+
+    /// All items of `appid` the user is subscribed to, via `IPublishedFileService/GetUserFiles`.
+    /// Returns None without a key or if a page could not be fetched.
+    pub async fn fetch_subscribed_items(&self, steam_id: &str, appid: &str) -> Option<Vec<ItemDetails>> {
+        let api_key = self.api_key.as_ref()?;
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let resp = self
+                .http
+                .get("https://api.steampowered.com/IPublishedFileService/GetUserFiles/v1/")
+                .query(&[
+                    ("key", api_key.as_str()),
+                    ("steamid", steam_id),
+                    ("appid", appid),
+                    ("type", "mysubscriptions"),
+                    ("return_tags", "true"),
+                    ("return_previews", "true"),
+                    ("numperpage", "100"),
+                    ("page", &page.to_string()),
+                ])
+                .send()
+                .await
+                .ok()?;
+            if !resp.status().is_success() {
+                return None;
+            }
+            let json = resp.json::<Value>().await.ok()?;
+            let total = json["response"]["total"].as_u64().unwrap_or(0) as usize;
+            let page_items: Vec<ItemDetails> = json["response"]["publishedfiledetails"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(ItemDetails::from_json)
+                .collect();
+
+            let empty = page_items.is_empty();
+            items.extend(page_items);
+            if empty || items.len() >= total {
+                break;
+            }
+        }
+
+        Some(items)
+    }
+
+    /// Fetch the apps (usually DLC) each mod depends on via `IPublishedFileService/GetAppDependencies`.
+    /// Only mods that could be checked are in the result; empty without an API key.
+    pub async fn fetch_app_dependencies(&self, mod_ids: &[String]) -> HashMap<String, Vec<u32>> {
+        let mut dependencies = HashMap::new();
+        let Some(api_key) = self.api_key.clone() else {
+            return dependencies;
+        };
+        self.run_batched(
+            mod_ids,
+            move |client, chunk| {
+                let api_key = api_key.clone();
+                async move {
+                    // The endpoint takes a single item per request
+                    let mut results = Vec::new();
+                    for id in chunk {
+                        let Ok(resp) = client
+                            .get("https://api.steampowered.com/IPublishedFileService/GetAppDependencies/v1/")
+                            .query(&[("key", api_key.as_str()), ("publishedfileid", id.as_str())])
+                            .send()
+                            .await
+                        else {
+                            continue;
+                        };
+                        if let Ok(json) = resp.json::<Value>().await {
+                            let app_ids = json["response"]["app_ids"]
+                                .as_array()
+                                .into_iter()
+                                .flatten()
+                                .filter_map(|a| a.as_u64().map(|a| a as u32))
+                                .collect();
+                            results.push((id, app_ids));
+                        }
                     }
+                    results
                 }
+            },
+            |batch: Vec<(String, Vec<u32>)>| dependencies.extend(batch),
+        )
+        .await;
+        dependencies
+    }
+
+    /// Look up names of apps in the store; DLC also reports the game it belongs to.
+    pub async fn fetch_app_info(&self, appids: &[u32]) -> HashMap<u32, AppInfo> {
+        let mut apps = HashMap::new();
+        for appid in appids {
+            let Ok(resp) = self
+                .http
+                .get("https://store.steampowered.com/api/appdetails")
+                .query(&[("appids", appid.to_string()), ("filters", "basic".to_string())])
+                .send()
+                .await
+            else {
+                continue;
+            };
+            if let Ok(json) = resp.json::<Value>().await {
+                let data = &json[appid.to_string()]["data"];
+                if let Some(name) = data["name"].as_str() {
+                    let dlc_for = data["fullgame"]["appid"]
+                        .as_str()
+                        .and_then(|a| a.parse().ok())
+                        .or_else(|| data["fullgame"]["appid"].as_u64().map(|a| a as u32));
+                    apps.insert(*appid, AppInfo { name: name.to_string(), dlc_for });
+                }
+            }
+            sleep(Duration::from_millis(300)).await;
+        }
+        apps
+    }
+
+    /// Check the API key with a cheap authenticated call. Steam answers 403 for unknown keys.
+    pub async fn check_api_key(&self) -> bool {
+        let Some(api_key) = &self.api_key else {
+            return false;
+        };
+        match self
+            .http
+            .get("https://api.steampowered.com/ISteamWebAPIUtil/GetSupportedAPIList/v1/")
+            .query(&[("key", api_key.as_str())])
+            .send()
+            .await
+        {
+            Ok(resp) => resp.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    /// Split `ids` into batches of 50 and run `fetch` on up to `concurrency` of them at once.
+    async fn run_batched<T, Fut>(
+        &self,
+        ids: &[String],
+        fetch: impl Fn(Client, Vec<String>) -> Fut,
+        mut on_batch: impl FnMut(T),
+    ) where
+        T: Send + 'static,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let slots = Arc::new(Semaphore::new(self.concurrency));
+        let mut batches = JoinSet::new();
+
+        for chunk in ids.chunks(50) {
+            let slots = slots.clone();
+            let request = fetch(self.http.clone(), chunk.to_vec());
+            batches.spawn(async move {
+                let _slot = slots.acquire_owned().await.ok()?;
+                let batch = request.await;
+                // Keep the slot a moment so we stay polite towards the API
+                sleep(Duration::from_secs(1)).await;
+                Some(batch)
+            });
+        }
+
+        // Dropping the JoinSet (e.g. when the caller is aborted) cancels the remaining batches
+        while let Some(joined) = batches.join_next().await {
+            if let Ok(Some(batch)) = joined {
+                on_batch(batch);
             }
         }
+    }
+}
+
+async fn fetch_batch(client: &Client, chunk: &[String]) -> Vec<ItemDetails> {
+    let mut results = Vec::new();
+    let mut params: Vec<(String, String)> = Vec::new();
+    params.push(("itemcount".to_string(), chunk.len().to_string()));
+
+    for (i, id) in chunk.iter().enumerate() {
+        params.push((format!("publishedfileids[{}]", i), id.clone()));
+    }
 
-        // Delay between batches
-        sleep(Duration::from_secs(1)).await;
+    if let Ok(resp) = client
+        .post("https://api.steampowered.com/ISteamRemoteStorage/GetPublishedFileDetails/v1/")
+        .form(&params) // reqwest accepts Vec<(String, String)>
+        .send()
+        .await
+        && let Ok(json) = resp.json::<Value>().await
+        && let Some(items) = json["response"]["publishedfiledetails"].as_array()
+    {
+        for item in items {
+            results.push(ItemDetails::from_json(item));
+        }
     }
 
     results
-}
\ No newline at end of file
+}
+
+/// Query `GetCollectionDetails` for the given IDs.
+/// Returns (parent_id -> [(child_id, filetype)]).
+async fn fetch_collection_children(
+    client: &Client,
+    ids: &[String],
+) -> Option<HashMap<String, Vec<(String, u64)>>> {
+    let mut params: Vec<(String, String)> = Vec::new();
+    params.push(("collectioncount".to_string(), ids.len().to_string()));
+    for (i, id) in ids.iter().enumerate() {
+        params.push((format!("publishedfileids[{}]", i), id.clone()));
+    }
+
+    let resp = client
+        .post("https://api.steampowered.com/ISteamRemoteStorage/GetCollectionDetails/v1/")
+        .form(&params)
+        .send()
+        .await
+        .ok()?;
+    let json = resp.json::<Value>().await.ok()?;
+
+    let mut result = HashMap::new();
+    for entry in json["response"]["collectiondetails"].as_array()? {
+        let parent = entry["publishedfileid"].as_str().unwrap_or("").to_string();
+        let children = entry["children"]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|c| {
+                        let id = c["publishedfileid"].as_str()?.to_string();
+                        Some((id, c["filetype"].as_u64().unwrap_or(0)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        result.insert(parent, children);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_workshop_ids_from_ids_and_urls() {
+        let base = "https://steamcommunity.com/sharedfiles/filedetails/";
+        assert_eq!(parse_workshop_id(" 2009463077 ").as_deref(), Some("2009463077"));
+        assert_eq!(parse_workshop_id(&format!("{}?id=2009463077", base)).as_deref(), Some("2009463077"));
+        assert_eq!(
+            parse_workshop_id(&format!("{}?appid=294100&id=2009463077", base)).as_deref(),
+            Some("2009463077")
+        );
+        assert_eq!(
+            parse_workshop_id(&format!("{}?id=2009463077&searchtext=harmony", base)).as_deref(),
+            Some("2009463077")
+        );
+    }
+
+    #[test]
+    fn rejects_inputs_without_an_id() {
+        assert_eq!(parse_workshop_id(""), None);
+        assert_eq!(parse_workshop_id("harmony"), None);
+        assert_eq!(parse_workshop_id("https://steamcommunity.com/app/294100/workshop/?appid=294100"), None);
+        assert_eq!(parse_workshop_id("https://steamcommunity.com/sharedfiles/filedetails/?id=abc"), None);
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::future::Future;
+
+use crate::steam::{ItemDetails, WorkshopClient};
+
+/// Where subscription lists come from; the Steam Web API in the app, a fixed list in tests.
+pub trait SubscriptionSource {
+    fn fetch_subscribed_items(
+        &self,
+        steam_id: &str,
+        appid: &str,
+    ) -> impl Future<Output = Option<Vec<ItemDetails>>> + Send;
+}
+
+impl SubscriptionSource for WorkshopClient {
+    fn fetch_subscribed_items(
+        &self,
+        steam_id: &str,
+        appid: &str,
+    ) -> impl Future<Output = Option<Vec<ItemDetails>>> + Send {
+        WorkshopClient::fetch_subscribed_items(self, steam_id, appid)
+    }
+}
+
+/// Subscribed Workshop items compared with the items installed on disk.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubscriptionDiff {
+    pub subscribed_only: Vec<(String, String)>, // (id, title): subscribed but not installed
+    pub installed_only: Vec<String>,            // installed but no longer subscribed
+    pub both: Vec<String>,
+}
+
+impl SubscriptionDiff {
+    pub fn compute(subscribed: &[ItemDetails], installed: &[String]) -> Self {
+        let subscribed_ids: HashSet<&str> = subscribed.iter().map(|d| d.id.as_str()).collect();
+        let installed_ids: HashSet<&str> = installed.iter().map(|id| id.as_str()).collect();
+
+        let mut diff = Self {
+            subscribed_only: subscribed
+                .iter()
+                .filter(|d| !installed_ids.contains(d.id.as_str()))
+                .map(|d| (d.id.clone(), d.title.clone()))
+                .collect(),
+            installed_only: installed
+                .iter()
+                .filter(|id| !subscribed_ids.contains(id.as_str()))
+                .cloned()
+                .collect(),
+            both: installed
+                .iter()
+                .filter(|id| subscribed_ids.contains(id.as_str()))
+                .cloned()
+                .collect(),
+        };
+        diff.subscribed_only.sort();
+        diff.installed_only.sort();
+        diff.both.sort();
+        diff
+    }
+}
+
+/// Fetch the user's subscriptions for `appid` and diff them against the installed item IDs.
+pub async fn compare_subscriptions<S: SubscriptionSource>(
+    source: &S,
+    steam_id: &str,
+    appid: &str,
+    installed: &[String],
+) -> Option<SubscriptionDiff> {
+    let subscribed = source.fetch_subscribed_items(steam_id, appid).await?;
+    Some(SubscriptionDiff::compute(&subscribed, installed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockSource(Option<Vec<ItemDetails>>);
+
+    impl SubscriptionSource for MockSource {
+        fn fetch_subscribed_items(
+            &self,
+            _steam_id: &str,
+            _appid: &str,
+        ) -> impl Future<Output = Option<Vec<ItemDetails>>> + Send {
+            std::future::ready(self.0.clone())
+        }
+    }
+
+    fn item(id: &str, title: &str) -> ItemDetails {
+        ItemDetails { id: id.into(), title: title.into(), ..Default::default() }
+    }
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn splits_into_three_lists() {
+        let source = MockSource(Some(vec![item("1", "One"), item("2", "Two"), item("3", "Three")]));
+        let diff = compare_subscriptions(&source, "7656", "294100", &ids(&["2", "3", "4"]))
+            .await
+            .unwrap();
+
+        assert_eq!(diff.subscribed_only, vec![("1".to_string(), "One".to_string())]);
+        assert_eq!(diff.installed_only, ids(&["4"]));
+        assert_eq!(diff.both, ids(&["2", "3"]));
+    }
+
+    #[tokio::test]
+    async fn failed_fetch_yields_none() {
+        let source = MockSource(None);
+        assert!(compare_subscriptions(&source, "7656", "294100", &ids(&["1"])).await.is_none());
+    }
+
+    #[test]
+    fn nothing_subscribed_means_everything_is_installed_only() {
+        let diff = SubscriptionDiff::compute(&[], &ids(&["5", "6"]));
+        assert!(diff.subscribed_only.is_empty());
+        assert!(diff.both.is_empty());
+        assert_eq!(diff.installed_only, ids(&["5", "6"]));
+    }
+}
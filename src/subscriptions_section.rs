@@ -0,0 +1,82 @@
+use egui::{Color32, Ui};
+
+use crate::state::{GameEntry, SubscriptionState};
+use crate::widgets;
+
+pub struct SubscriptionsSection;
+
+impl SubscriptionsSection {
+    /// Returns true when the user asks to compare subscriptions.
+    pub fn ui(game: &GameEntry, steam_id: Option<&str>, has_api_key: bool, ui: &mut Ui) -> bool {
+        let mut compare = false;
+
+        egui::CollapsingHeader::new("Subscriptions vs. installed")
+            .default_open(false)
+            .show(ui, |ui| {
+                let Some(steam_id) = steam_id else {
+                    ui.colored_label(Color32::YELLOW, "No Steam account found. Set your SteamID64 in Settings.");
+                    return;
+                };
+                if !has_api_key {
+                    ui.colored_label(Color32::YELLOW, "Comparing subscriptions needs a Steam Web API key (see Settings).");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("Account: {}", steam_id));
+                    let loading = matches!(game.subscriptions, SubscriptionState::Loading);
+                    if ui.add_enabled(!loading, egui::Button::new("Compare subscriptions")).clicked() {
+                        compare = true;
+                    }
+                });
+
+                match &game.subscriptions {
+                    SubscriptionState::Empty => {}
+                    SubscriptionState::Loading => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Loading subscriptions…");
+                        });
+                    }
+                    SubscriptionState::Failed => {
+                        ui.colored_label(Color32::RED, "Could not load subscriptions (is the profile's Workshop list public?)");
+                    }
+                    SubscriptionState::Loaded(diff) => {
+                        egui::CollapsingHeader::new(format!(
+                            "Subscribed but not installed ({})",
+                            diff.subscribed_only.len()
+                        ))
+                        .id_salt("subs_subscribed_only")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for (id, title) in &diff.subscribed_only {
+                                widgets::item_link(ui, id, title, Color32::RED);
+                            }
+                        });
+
+                        egui::CollapsingHeader::new(format!(
+                            "Installed but not subscribed ({})",
+                            diff.installed_only.len()
+                        ))
+                        .id_salt("subs_installed_only")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for id in &diff.installed_only {
+                                widgets::item_link(ui, id, &game.title_for(id), Color32::YELLOW);
+                            }
+                        });
+
+                        egui::CollapsingHeader::new(format!("Subscribed and installed ({})", diff.both.len()))
+                            .id_salt("subs_both")
+                            .show(ui, |ui| {
+                                for id in &diff.both {
+                                    widgets::item_link(ui, id, &game.title_for(id), Color32::GREEN);
+                                }
+                            });
+                    }
+                }
+            });
+
+        compare
+    }
+}
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::mpsc::{Receiver, Sender}};
+
+use egui::{ColorImage, TextureHandle, TextureOptions};
+use tokio::runtime::Runtime;
+
+use crate::config::ensure_data_dir;
+
+/// Edge length thumbnails are downscaled to before they are cached on disk.
+const THUMBNAIL_SIZE: u32 = 128;
+
+enum ThumbnailState {
+    Loading,
+    Ready(TextureHandle),
+    Failed,
+}
+
+/// Lazily downloaded Workshop preview images, cached as small PNGs in `data/thumbnails`.
+pub struct Thumbnails {
+    textures: HashMap<String, ThumbnailState>,
+    tx: Sender<(String, Option<ColorImage>)>,
+    rx: Receiver<(String, Option<ColorImage>)>,
+}
+
+impl Thumbnails {
+    pub fn new() -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        Self { textures: HashMap::new(), tx, rx }
+    }
+
+    /// Returns the texture if it is ready, otherwise starts loading it in the background.
+    pub fn get(&mut self, rt: &Runtime, id: &str, url: &str) -> Option<&TextureHandle> {
+        if !self.textures.contains_key(id) {
+            self.textures.insert(id.to_string(), ThumbnailState::Loading);
+            let tx = self.tx.clone();
+            let id = id.to_string();
+            let url = url.to_string();
+            rt.spawn(async move {
+                let image = load_thumbnail(&id, &url).await;
+                let _ = tx.send((id, image));
+            });
+        }
+        match self.textures.get(id) {
+            Some(ThumbnailState::Ready(texture)) => Some(texture),
+            _ => None,
+        }
+    }
+
+    /// Turn finished downloads into textures. Returns true if anything changed.
+    pub fn poll(&mut self, ctx: &egui::Context) -> bool {
+        let mut changed = false;
+        while let Ok((id, image)) = self.rx.try_recv() {
+            let state = match image {
+                Some(image) => ThumbnailState::Ready(ctx.load_texture(
+                    format!("thumbnail_{}", id),
+                    image,
+                    TextureOptions::LINEAR,
+                )),
+                None => ThumbnailState::Failed,
+            };
+            self.textures.insert(id, state);
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.textures.values().any(|t| matches!(t, ThumbnailState::Loading))
+    }
+}
+
+fn cache_path(id: &str) -> PathBuf {
+    let dir = ensure_data_dir().join("thumbnails");
+    let _ = fs::create_dir_all(&dir);
+    dir.join(format!("{}.png", id))
+}
+
+async fn load_thumbnail(id: &str, url: &str) -> Option<ColorImage> {
+    let path = cache_path(id);
+    // Decoding, resizing and saving are blocking work, kept off the runtime's workers
+    let cached = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || image::open(&path).ok().map(|cached| to_color_image(&cached)))
+            .await
+            .ok()?
+    };
+    if cached.is_some() {
+        return cached;
+    }
+
+    let bytes = reqwest::get(url).await.ok()?.bytes().await.ok()?;
+    tokio::task::spawn_blocking(move || {
+        let thumbnail = image::load_from_memory(&bytes)
+            .ok()?
+            .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+        let _ = thumbnail.save(&path);
+        Some(to_color_image(&thumbnail))
+    })
+    .await
+    .ok()?
+}
+
+fn to_color_image(image: &image::DynamicImage) -> ColorImage {
+    let rgba = image.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    ColorImage::from_rgba_unmultiplied(size, rgba.as_raw())
+}
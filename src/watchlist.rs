@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{read_json, write_json};
+use crate::steam::{self, ItemDetails};
+
+/// A Workshop item followed without being installed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchedItem {
+    pub id: String,
+    pub title: Option<String>,
+    pub time_updated: Option<String>, // latest remote update
+    pub seen_updated: Option<String>, // remote update the user last looked at
+}
+
+impl WatchedItem {
+    pub fn new(id: String) -> Self {
+        Self { id, ..Default::default() }
+    }
+
+    pub fn apply_details(&mut self, details: &ItemDetails) {
+        if details.removed {
+            return;
+        }
+        self.title = Some(details.title.clone());
+        self.time_updated = Some(details.time_updated.clone());
+        // The first fetch is the baseline, not a change
+        if self.seen_updated.is_none() {
+            self.seen_updated = self.time_updated.clone();
+        }
+    }
+
+    pub fn has_changed(&self) -> bool {
+        self.seen_updated.is_some() && self.time_updated != self.seen_updated
+    }
+
+    pub fn mark_seen(&mut self) {
+        self.seen_updated = self.time_updated.clone();
+    }
+}
+
+/// Workshop IDs typed or pasted into the watch field: URLs or IDs separated by whitespace or commas.
+pub fn parse_ids(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(steam::parse_workshop_id)
+        .collect()
+}
+
+/// Workshop IDs in a dropped Internet shortcut (`.url`), which holds the link on a `URL=` line.
+/// Bare numbers are ignored, only Workshop links count.
+pub fn parse_shortcut(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("URL="))
+        .filter_map(steam::parse_workshop_url)
+        .collect()
+}
+
+/// appid -> watched items
+pub fn load_watchlists() -> HashMap<String, Vec<WatchedItem>> {
+    read_json("watchlists.json").unwrap_or_default()
+}
+
+pub fn save_watchlists(watchlists: &HashMap<String, Vec<WatchedItem>>) {
+    let _ = write_json("watchlists.json", watchlists);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcuts_only_yield_workshop_links() {
+        let shortcut = "[InternetShortcut]\r\nURL=https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077\r\nIDList=\r\nHotKey=0\r\n";
+        assert_eq!(parse_shortcut(shortcut), ["2009463077"]);
+        assert!(parse_shortcut("[InternetShortcut]\nURL=https://example.com/?id=5\n").is_empty());
+        assert!(parse_shortcut("mods 2024\nURL=2024").is_empty());
+    }
+
+    #[test]
+    fn typed_text_accepts_ids_and_links() {
+        let text = "818773962, https://steamcommunity.com/sharedfiles/filedetails/?id=2009463077";
+        assert_eq!(parse_ids(text), ["818773962", "2009463077"]);
+    }
+}
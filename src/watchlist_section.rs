@@ -0,0 +1,86 @@
+use egui::{Color32, RichText, Ui};
+
+use crate::state::{Action, GameEntry};
+use crate::model::format_timestamp;
+use crate::steam;
+use crate::watchlist;
+
+pub struct WatchlistSection;
+
+impl WatchlistSection {
+    /// Returns the watchlist edits, including items dropped onto the window.
+    pub fn ui(game: &GameEntry, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = Vec::new();
+        let changed_count = game.watchlist.iter().filter(|w| w.has_changed()).count();
+        let title = if changed_count > 0 {
+            format!("Watchlist ({}, {} updated)", game.watchlist.len(), changed_count)
+        } else {
+            format!("Watchlist ({})", game.watchlist.len())
+        };
+
+        // Links dragged onto the window arrive as `.url` shortcuts; other files are ignored
+        let dropped = ui.input(|i| i.raw.dropped_files.clone());
+        for file in dropped {
+            let name = file.path.as_ref().map_or(file.name.clone(), |p| p.display().to_string());
+            if !name.to_lowercase().ends_with(".url") {
+                continue;
+            }
+            let content = match (&file.bytes, &file.path) {
+                (Some(bytes), _) => String::from_utf8_lossy(bytes).into_owned(),
+                (None, Some(path)) => std::fs::read_to_string(path).unwrap_or_default(),
+                (None, None) => continue,
+            };
+            actions.push(Action::Watch(watchlist::parse_shortcut(&content)));
+        }
+
+        egui::CollapsingHeader::new(title)
+            .id_salt("watchlist")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Workshop links or IDs:");
+                    let mut input = game.watch_input.clone();
+                    let response = ui.text_edit_singleline(&mut input);
+                    if response.changed() {
+                        actions.push(Action::SetWatchInput(input.clone()));
+                    }
+                    let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Watch").clicked() || enter {
+                        actions.push(Action::Watch(watchlist::parse_ids(&input)));
+                        actions.push(Action::SetWatchInput(String::new()));
+                    }
+                });
+
+                ui.label(RichText::new("Links can also be dragged onto the window. Details load with the next fetch.").weak());
+
+                if changed_count > 0 && ui.button("Mark all as seen").clicked() {
+                    actions.push(Action::MarkWatchedSeen(None));
+                }
+
+                for item in &game.watchlist {
+                    ui.horizontal(|ui| {
+                        let url = steam::workshop_url(&item.id);
+                        ui.hyperlink_to(&item.id, url);
+                        let title = item.title.as_deref().unwrap_or("<not fetched yet>");
+                        if item.has_changed() {
+                            ui.colored_label(Color32::YELLOW, format!("🔔 {}", title));
+                        } else {
+                            ui.label(title);
+                        }
+                        ui.label(format!("updated {}", format_timestamp(item.time_updated.as_deref())));
+                        if game.mods.iter().any(|m| m.id == item.id) {
+                            ui.colored_label(Color32::GREEN, "✅ Installed");
+                        }
+                        if item.has_changed() && ui.small_button("Seen").clicked() {
+                            actions.push(Action::MarkWatchedSeen(Some(item.id.clone())));
+                        }
+                        if ui.small_button("🗑").on_hover_text("Stop watching").clicked() {
+                            actions.push(Action::Unwatch(item.id.clone()));
+                        }
+                    });
+                }
+            });
+
+        actions
+    }
+}
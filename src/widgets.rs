@@ -0,0 +1,11 @@
+use egui::{Color32, Ui};
+
+use crate::steam;
+
+/// Workshop ID linking to its page, followed by the item's title.
+pub fn item_link(ui: &mut Ui, id: &str, title: &str, color: Color32) {
+    ui.horizontal(|ui| {
+        ui.hyperlink_to(id, steam::workshop_url(id));
+        ui.colored_label(color, title);
+    });
+}